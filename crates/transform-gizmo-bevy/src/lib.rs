@@ -34,7 +34,8 @@ use bevy::window::PrimaryWindow;
 use bevy_math::{DQuat, DVec3};
 use render::{DrawDataHandles, TransformGizmoRenderPlugin};
 use transform_gizmo::config::{
-    TransformPivotPoint, DEFAULT_SNAP_ANGLE, DEFAULT_SNAP_DISTANCE, DEFAULT_SNAP_SCALE,
+    TransformPivotPoint, DEFAULT_PRECISION_FACTOR, DEFAULT_SNAP_ANGLE, DEFAULT_SNAP_DISTANCE,
+    DEFAULT_SNAP_SCALE,
 };
 
 pub use transform_gizmo::{
@@ -70,20 +71,66 @@ impl Plugin for TransformGizmoPlugin {
 pub struct GizmoOptions {
     /// Modes to use in the gizmos
     pub gizmo_modes: EnumSet<GizmoMode>,
+    /// Directions that the gizmos can transform in.
+    pub enabled_directions: EnumSet<GizmoDirection>,
+    /// Directions of the rotation rings. With a single axis, only that ring is shown.
+    pub rotation_directions: EnumSet<GizmoDirection>,
+    /// Whether the gizmos transform in the XY plane only, for 2D editors.
+    pub planar_2d: bool,
     /// Orientation of the gizmo. This affects the behaviour of transformations.
     pub gizmo_orientation: GizmoOrientation,
     /// Orientation of the gizmo. This affects the behaviour of transformations.
     pub pivot_point: TransformPivotPoint,
+    /// How cursor movement is turned into rotation when dragging a rotation ring.
+    pub rotation_mode: RotationInputMode,
+    /// World space position to place the gizmos at and rotate around,
+    /// instead of the center of the targets.
+    pub pivot: Option<DVec3>,
+    /// Offset of the gizmos from the center of their targets.
+    pub offset: DVec3,
+    /// Whether the offset is given in world space or in the local space of the targets.
+    pub offset_orientation: GizmoOrientation,
+    /// Opacity of the gizmos, from 0.0 to 1.0. At 0.0, the gizmos are hidden
+    /// and cannot be interacted with.
+    pub opacity: f32,
+    /// Whether the gizmos can be interacted with. When false, the gizmos are
+    /// still drawn, but they cannot be picked or dragged.
+    pub interactive: bool,
+    /// Whether to add a dolly handle below the gizmos, for translating
+    /// along the view direction.
+    pub dolly_handle: bool,
+    /// Whether the size of the gizmos is fixed on the screen or in the world.
+    pub size_mode: SizeMode,
     /// Look and feel of the gizmo.
     pub visuals: GizmoVisuals,
     /// Whether snapping is enabled in the gizmo transformations.
+    /// The options are shared by all gizmo targets.
     pub snapping: bool,
     /// Angle increment for snapping rotations, in radians.
     pub snap_angle: f32,
     /// Distance increment for snapping translations.
     pub snap_distance: f32,
+    /// Distance increments for snapping translations along each axis of the gizmo.
+    /// Replaces `snap_distance` for the X, Y and Z translation handles and planes.
+    pub snap_distances: Option<Vec3>,
     /// Scale increment for snapping scalings.
     pub snap_scale: f32,
+    /// World space bounds that the gizmos can be translated within,
+    /// given as the minimum and maximum corners of a box.
+    pub translation_bounds: Option<(DVec3, DVec3)>,
+    /// Minimum and maximum scale that scaling can result in.
+    pub scale_clamp: Option<(f32, f32)>,
+    /// Whether scaling can go past zero into negative scales, mirroring the targets.
+    pub allow_negative_scale: bool,
+    /// Restricts picking to the viewport, expanded by this margin on each side.
+    /// With [`None`], the gizmos can be picked anywhere in the window.
+    pub pick_margin: Option<f32>,
+    /// Multiplier for the distance within which the cursor grabs a gizmo handle.
+    pub grab_radius_multiplier: f32,
+    /// How fast the gizmo moves relative to the cursor while Alt is held.
+    pub precision_factor: f32,
+    /// How fast scaling reacts to the pointer motion.
+    pub scale_sensitivity: f32,
     /// If `true`, all [`GizmoTarget`]s are transformed
     /// using a single gizmo. If `false`, each target
     /// has its own gizmo.
@@ -94,13 +141,32 @@ impl Default for GizmoOptions {
     fn default() -> Self {
         Self {
             gizmo_modes: EnumSet::only(GizmoMode::Rotate),
+            enabled_directions: EnumSet::all(),
+            rotation_directions: EnumSet::all(),
+            planar_2d: false,
             gizmo_orientation: GizmoOrientation::default(),
             pivot_point: TransformPivotPoint::default(),
+            rotation_mode: RotationInputMode::default(),
+            pivot: None,
+            offset: DVec3::ZERO,
+            offset_orientation: GizmoOrientation::Global,
+            opacity: 1.0,
+            interactive: true,
+            dolly_handle: false,
+            size_mode: SizeMode::default(),
             visuals: Default::default(),
             snapping: false,
             snap_angle: DEFAULT_SNAP_ANGLE,
             snap_distance: DEFAULT_SNAP_DISTANCE,
+            snap_distances: None,
             snap_scale: DEFAULT_SNAP_SCALE,
+            translation_bounds: None,
+            scale_clamp: None,
+            allow_negative_scale: false,
+            pick_margin: None,
+            grab_radius_multiplier: 1.0,
+            precision_factor: DEFAULT_PRECISION_FACTOR,
+            scale_sensitivity: 1.0,
             group_targets: true,
         }
     }
@@ -123,6 +189,15 @@ pub struct GizmoTarget {
     /// Whether the gizmo is currently being interacted with.
     pub(crate) is_active: bool,
 
+    /// Whether an interaction with the gizmo started this frame.
+    pub(crate) drag_started: bool,
+
+    /// Whether an interaction with the gizmo finished this frame.
+    pub(crate) drag_finished: bool,
+
+    /// Whether an interaction with the gizmo was cancelled this frame.
+    pub(crate) drag_cancelled: bool,
+
     /// This gets replaced with the result of the most recent
     /// gizmo interaction that affected this entity.
     pub(crate) latest_result: Option<GizmoResult>,
@@ -139,6 +214,22 @@ impl GizmoTarget {
         self.is_active
     }
 
+    /// Whether an interaction with the gizmo started this frame.
+    pub fn drag_started(&self) -> bool {
+        self.drag_started
+    }
+
+    /// Whether an interaction with the gizmo finished this frame.
+    pub fn drag_finished(&self) -> bool {
+        self.drag_finished
+    }
+
+    /// Whether an interaction with the gizmo was cancelled with Escape this frame.
+    /// The target transforms were returned to where they were when the interaction started.
+    pub fn drag_cancelled(&self) -> bool {
+        self.drag_cancelled
+    }
+
     /// This gets replaced with the result of the most recent
     /// gizmo interaction that affected this entity.
     pub fn latest_result(&self) -> Option<GizmoResult> {
@@ -163,6 +254,8 @@ fn update_gizmos(
     q_gizmo_camera: Query<(&Camera, &Transform), With<GizmoCamera>>,
     mut q_targets: Query<(Entity, &mut Transform, &mut GizmoTarget), Without<GizmoCamera>>,
     mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    touches: Res<Touches>,
     gizmo_options: Res<GizmoOptions>,
     mut gizmo_storage: ResMut<GizmoStorage>,
     mut last_cursor_pos: Local<Vec2>,
@@ -200,13 +293,38 @@ fn update_gizmos(
         projection_matrix: projection_matrix.as_dmat4().into(),
         viewport,
         modes: gizmo_options.gizmo_modes,
+        enabled_directions: gizmo_options.enabled_directions,
+        rotation_directions: gizmo_options.rotation_directions,
+        planar_2d: gizmo_options.planar_2d,
         orientation: gizmo_options.gizmo_orientation,
         pivot_point: gizmo_options.pivot_point,
+        rotation_mode: gizmo_options.rotation_mode,
+        pivot: gizmo_options.pivot.map(Into::into),
+        offset: gizmo_options.offset.into(),
+        offset_orientation: gizmo_options.offset_orientation,
+        opacity: gizmo_options.opacity,
+        interactive: gizmo_options.interactive,
+        dolly_handle: gizmo_options.dolly_handle,
+        size_mode: gizmo_options.size_mode,
         visuals: gizmo_options.visuals,
         snapping: gizmo_options.snapping,
         snap_angle: gizmo_options.snap_angle,
         snap_distance: gizmo_options.snap_distance,
+        snap_distances: gizmo_options.snap_distances.map(Into::into),
         snap_scale: gizmo_options.snap_scale,
+        translation_bounds: gizmo_options
+            .translation_bounds
+            .map(|(min, max)| (min.into(), max.into())),
+        scale_clamp: gizmo_options.scale_clamp,
+        allow_negative_scale: gizmo_options.allow_negative_scale,
+        pick_margin: gizmo_options.pick_margin,
+        // Window cursor positions have a top-left origin
+        viewport_y_down: true,
+        // Bevy is right-handed, also with its reverse-Z orthographic projections
+        handedness: Some(Handedness::Right),
+        grab_radius_multiplier: gizmo_options.grab_radius_multiplier,
+        precision_factor: gizmo_options.precision_factor,
+        scale_sensitivity: gizmo_options.scale_sensitivity,
         pixels_per_point: scale_factor,
     };
 
@@ -214,6 +332,17 @@ fn update_gizmos(
         cursor_pos: (cursor_pos.x, cursor_pos.y),
         drag_started: mouse.just_pressed(MouseButton::Left),
         dragging: mouse.any_pressed([MouseButton::Left]),
+        precision: keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+        snap_toggle: keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+        cancel: keyboard.just_pressed(KeyCode::Escape),
+        axis_lock_key: [
+            (KeyCode::KeyX, GizmoDirection::X),
+            (KeyCode::KeyY, GizmoDirection::Y),
+            (KeyCode::KeyZ, GizmoDirection::Z),
+        ]
+        .into_iter()
+        .find_map(|(key, direction)| keyboard.just_pressed(key).then_some(direction)),
+        pinch_zoom: pinch_zoom(&touches),
     };
 
     let mut target_entities: Vec<Entity> = vec![];
@@ -257,6 +386,9 @@ fn update_gizmos(
 
         gizmo_target.is_active = gizmo_result.is_some();
         gizmo_target.is_focused = is_focused;
+        gizmo_target.drag_started = gizmo.drag_started();
+        gizmo_target.drag_finished = gizmo.drag_finished();
+        gizmo_target.drag_cancelled = gizmo.drag_cancelled();
 
         if let Some((_, updated_targets)) = &gizmo_result {
             let Some(result_transform) = updated_targets.first() else {
@@ -290,10 +422,16 @@ fn update_gizmos(
         );
 
         let is_focused = gizmo.is_focused();
+        let drag_started = gizmo.drag_started();
+        let drag_finished = gizmo.drag_finished();
+        let drag_cancelled = gizmo.drag_cancelled();
 
         for (i, (_, mut target_transform, mut gizmo_target)) in q_targets.iter_mut().enumerate() {
             gizmo_target.is_active = gizmo_result.is_some();
             gizmo_target.is_focused = is_focused;
+            gizmo_target.drag_started = drag_started;
+            gizmo_target.drag_finished = drag_finished;
+            gizmo_target.drag_cancelled = drag_cancelled;
 
             if let Some((_, updated_targets)) = &gizmo_result {
                 let Some(result_transform) = updated_targets.get(i) else {
@@ -313,6 +451,19 @@ fn update_gizmos(
     gizmo_storage.target_entities = target_entities;
 }
 
+/// Zoom ratio of a two-finger pinch since the previous frame, if two touches are pressed.
+fn pinch_zoom(touches: &Touches) -> Option<f32> {
+    let mut pressed = touches.iter();
+    let (first, second) = (pressed.next()?, pressed.next()?);
+
+    let previous_distance = first
+        .previous_position()
+        .distance(second.previous_position());
+    let distance = first.position().distance(second.position());
+
+    (previous_distance > 0.0).then(|| distance / previous_distance)
+}
+
 fn draw_gizmos(
     gizmo_storage: Res<GizmoStorage>,
     mut draw_data_assets: ResMut<Assets<render::GizmoDrawData>>,
@@ -336,15 +487,15 @@ fn draw_gizmos(
         asset
             .0
             .vertices
-            .extend(draw_data.vertices.into_iter().map(|vert| {
+            .extend(draw_data.vertices.iter().map(|vert| {
                 [
                     ((vert[0] - viewport.left()) / viewport.width()) * 2.0 - 1.0,
                     ((vert[1] - viewport.top()) / viewport.height()) * 2.0 - 1.0,
                 ]
             }));
 
-        asset.0.colors = draw_data.colors;
-        asset.0.indices = draw_data.indices;
+        asset.0.colors.clone_from(&draw_data.colors);
+        asset.0.indices.clone_from(&draw_data.indices);
 
         if is_new_asset {
             let asset = draw_data_assets.add(bevy_draw_data);
@@ -44,7 +44,7 @@
 //! ```
 //!
 //!
-use egui::{epaint::Vertex, Mesh, PointerButton, Pos2, Rgba, Ui};
+use egui::{epaint::Vertex, Key, Mesh, PointerButton, Pos2, Rgba, Ui};
 
 use transform_gizmo::math::Transform;
 pub use transform_gizmo::*;
@@ -92,6 +92,19 @@ impl GizmoExt for Gizmo {
                 drag_started: ui
                     .input(|input| input.pointer.button_pressed(PointerButton::Primary)),
                 dragging: ui.input(|input| input.pointer.button_down(PointerButton::Primary)),
+                precision: ui.input(|input| input.modifiers.alt),
+                snap_toggle: ui.input(|input| input.modifiers.command),
+                cancel: ui.input(|input| input.key_pressed(Key::Escape)),
+                axis_lock_key: ui.input(|input| {
+                    [
+                        (Key::X, GizmoDirection::X),
+                        (Key::Y, GizmoDirection::Y),
+                        (Key::Z, GizmoDirection::Z),
+                    ]
+                    .into_iter()
+                    .find_map(|(key, direction)| input.key_pressed(key).then_some(direction))
+                }),
+                pinch_zoom: ui.input(|input| input.multi_touch().map(|touch| touch.zoom_delta)),
             },
             targets,
         );
@@ -99,12 +112,12 @@ impl GizmoExt for Gizmo {
         let draw_data = self.draw();
 
         ui.painter().with_clip_rect(egui_viewport).add(Mesh {
-            indices: draw_data.indices,
+            indices: draw_data.indices.clone(),
             vertices: draw_data
                 .vertices
-                .into_iter()
-                .zip(draw_data.colors)
-                .map(|(pos, [r, g, b, a])| Vertex {
+                .iter()
+                .zip(&draw_data.colors)
+                .map(|(&pos, &[r, g, b, a])| Vertex {
                     pos: pos.into(),
                     uv: Pos2::default(),
                     color: Rgba::from_rgba_premultiplied(r, g, b, a).into(),
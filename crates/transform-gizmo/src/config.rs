@@ -1,12 +1,16 @@
+use std::fmt::{self, Debug};
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 pub use ecolor::Color32;
 
-use emath::Rect;
+use emath::{Pos2, Rect};
 use enumset::{enum_set, EnumSet, EnumSetType};
 
+use crate::gizmo::Ray;
 use crate::math::{
-    screen_to_world, world_to_screen, DMat4, DQuat, DVec3, DVec4, Transform, Vec4Swizzles,
+    round_to_interval, screen_to_world, world_to_screen, DMat4, DQuat, DVec3, DVec4, Transform,
+    Vec4Swizzles,
 };
 
 /// The default snapping distance for rotation in radians
@@ -15,36 +19,246 @@ pub const DEFAULT_SNAP_ANGLE: f32 = std::f32::consts::PI / 32.0;
 pub const DEFAULT_SNAP_DISTANCE: f32 = 0.1;
 /// The default snapping distance for scale
 pub const DEFAULT_SNAP_SCALE: f32 = 0.1;
+/// The default motion scale of precision mode
+pub const DEFAULT_PRECISION_FACTOR: f32 = 0.1;
+/// The smallest allowed snapping distance for rotation in radians.
+///
+/// Smaller values of [`GizmoConfig::snap_angle`] are clamped to this.
+///
+/// ```
+/// # use transform_gizmo::{config::MIN_SNAP_ANGLE, Gizmo, GizmoConfig};
+/// let gizmo = Gizmo::new(GizmoConfig {
+///     snap_angle: 1e-9,
+///     ..Default::default()
+/// });
+///
+/// assert_eq!(gizmo.config().snap_angle, MIN_SNAP_ANGLE);
+/// ```
+pub const MIN_SNAP_ANGLE: f32 = 1e-3;
+/// The default number of segments in a full circle of the gizmo shapes
+pub const DEFAULT_CIRCLE_SEGMENTS: usize = 126;
+/// The smallest allowed number of segments in a full circle.
+///
+/// Smaller values of [`GizmoVisuals::circle_segments`] are clamped to this.
+///
+/// ```
+/// # use transform_gizmo::{config::MIN_CIRCLE_SEGMENTS, Gizmo, GizmoConfig, GizmoVisuals};
+/// let gizmo = Gizmo::new(GizmoConfig {
+///     visuals: GizmoVisuals {
+///         circle_segments: 3,
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// });
+///
+/// assert_eq!(gizmo.config().visuals.circle_segments, MIN_CIRCLE_SEGMENTS);
+/// ```
+pub const MIN_CIRCLE_SEGMENTS: usize = 8;
+/// The smallest allowed lower bound of [`GizmoConfig::scale_clamp`].
+///
+/// Smaller lower bounds, including zero and negative ones, are raised to this.
+pub const MIN_SCALE_CLAMP: f32 = 1e-4;
+/// Distance from the gizmo center, relative to [`GizmoVisuals::gizmo_size`],
+/// within which the pointer grows an idle gizmo back to full size
+const IDLE_PROXIMITY: f32 = 1.5;
 
 /// Configuration of a gizmo.
 ///
 /// Defines how the gizmo is drawn to the screen and
 /// how it can be interacted with.
-#[derive(Debug, Copy, Clone)]
+///
+/// With the `serde` feature, the config can be serialized for storing user preferences.
+/// The camera matrices, the viewport and `pixels_per_point` come from the runtime
+/// state of the camera, so they are not serialized and deserialize to their defaults.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct GizmoConfig {
     /// View matrix for the gizmo, aligning it with the camera's viewpoint.
+    ///
+    /// **The matrix is row-major.** Converting a glam matrix with `.into()` handles this
+    /// automatically. For raw column-major data, use [`GizmoConfig::with_view_column_major`].
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub view_matrix: mint::RowMatrix4<f64>,
     /// Projection matrix for the gizmo, determining how it is projected onto the screen.
+    ///
+    /// **The matrix is row-major.** Converting a glam matrix with `.into()` handles this
+    /// automatically. For raw column-major data, use [`GizmoConfig::with_projection_column_major`].
+    ///
+    /// Both perspective and orthographic projections are supported. The gizmo
+    /// is drawn at the same size on the screen with either of them.
+    ///
+    /// ```
+    /// # use transform_gizmo::prelude::*;
+    /// # use transform_gizmo::math::{DMat4, DVec3, Pos2, Transform};
+    /// let viewport = Rect::from_min_max(Pos2::ZERO, Pos2::new(800.0, 600.0));
+    /// let view_matrix = DMat4::look_at_rh(DVec3::new(0.0, 0.0, 5.0), DVec3::ZERO, DVec3::Y);
+    ///
+    /// let bounds_width = |projection_matrix: DMat4| {
+    ///     let mut gizmo = Gizmo::new(GizmoConfig {
+    ///         view_matrix: view_matrix.into(),
+    ///         projection_matrix: projection_matrix.into(),
+    ///         viewport,
+    ///         ..Default::default()
+    ///     });
+    ///     gizmo.update(GizmoInteraction::default(), &[Transform::default()]);
+    ///     gizmo.screen_bounds().unwrap().width()
+    /// };
+    ///
+    /// let perspective = bounds_width(DMat4::perspective_rh(1.0, 800.0 / 600.0, 0.1, 100.0));
+    /// let orthographic = bounds_width(DMat4::orthographic_rh(-4.0, 4.0, -3.0, 3.0, 0.1, 100.0));
+    /// let zoomed_out = bounds_width(DMat4::orthographic_rh(-40.0, 40.0, -30.0, 30.0, 0.1, 100.0));
+    ///
+    /// assert!((orthographic - perspective).abs() < 1.0);
+    /// assert!((zoomed_out - orthographic).abs() < 1.0);
+    /// ```
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub projection_matrix: mint::RowMatrix4<f64>,
     /// Screen area where the gizmo is displayed.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub viewport: Rect,
     /// The gizmo's operation modes.
     pub modes: EnumSet<GizmoMode>,
+    /// Directions that can be transformed in. Subgizmos of the other directions
+    /// are neither drawn nor pickable. A plane subgizmo is available when both
+    /// of the axes spanning it are enabled, so enabling only X and Z leaves
+    /// the XZ plane. [`GizmoDirection::View`] controls the view axis subgizmos,
+    /// such as free translation and arcball rotation.
+    pub enabled_directions: EnumSet<GizmoDirection>,
+    /// Directions of the rotation rings, in addition to [`GizmoConfig::enabled_directions`].
+    /// [`GizmoDirection::View`] is the view ring and the arcball, which are also left out
+    /// when only a single axis is left.
+    pub rotation_directions: EnumSet<GizmoDirection>,
+    /// Whether the gizmo transforms in the XY plane only, for 2D editors where the camera
+    /// looks along the Z axis. The targets are never moved, rotated or scaled along the Z axis,
+    /// and the gizmo is not turned to face a tilted camera.
+    pub planar_2d: bool,
     /// Determines the gizmo's orientation relative to global or local axes.
     pub orientation: GizmoOrientation,
     /// Pivot point for transformations
     pub pivot_point: TransformPivotPoint,
+    /// How cursor movement is turned into rotation when dragging a rotation ring.
+    pub rotation_mode: RotationInputMode,
+    /// World space position to place the gizmo at, instead of the center of the targets.
+    ///
+    /// With [`TransformPivotPoint::MedianPoint`], the targets rotate around this point,
+    /// orbiting it. The returned target transforms include the translation of the orbit.
+    /// During a translation drag the gizmo follows the drag, and afterwards it returns
+    /// to this point unless the point is updated.
+    pub pivot: Option<mint::Vector3<f64>>,
+    /// Offset of the gizmo from the center of the targets, for example to place the
+    /// gizmo at the center of a mesh whose origin is at its base. Ignored when
+    /// [`GizmoConfig::pivot`] is set.
+    ///
+    /// The offset only moves the gizmo. The returned target transforms still describe
+    /// the target origins. With [`TransformPivotPoint::MedianPoint`], the targets
+    /// rotate around the offset gizmo position.
+    pub offset: mint::Vector3<f64>,
+    /// Whether [`GizmoConfig::offset`] is given in world space or in the local space
+    /// of the targets, in which case the offset rotates along with the targets.
+    pub offset_orientation: GizmoOrientation,
     /// Toggles snapping to predefined increments during transformations for precision.
+    /// Can be inverted temporarily with [`GizmoInteraction::snap_toggle`](crate::GizmoInteraction::snap_toggle).
+    ///
+    /// All snapping state is stored per [`Gizmo`](crate::Gizmo), including custom
+    /// snapping functions and scale targets. Gizmos created from the same base
+    /// config can snap independently in the same frame.
+    ///
+    /// ```
+    /// # use transform_gizmo::{Gizmo, GizmoConfig, GizmoMode};
+    /// let base = GizmoConfig::default();
+    ///
+    /// let mut precise = Gizmo::new(GizmoConfig { snapping: true, ..base });
+    /// precise.set_snap_fn(GizmoMode::Rotate, |angle| angle.round());
+    /// let freehand = Gizmo::new(GizmoConfig { snapping: false, ..base });
+    ///
+    /// assert!(precise.config().snapping);
+    /// assert!(!freehand.config().snapping);
+    /// ```
     pub snapping: bool,
-    /// Angle increment for snapping rotations, in radians.
+    /// Angle increment for snapping rotations, in radians. Clamped between [`MIN_SNAP_ANGLE`]
+    /// and a full turn, and non-finite values fall back to the default.
     pub snap_angle: f32,
     /// Distance increment for snapping translations.
     pub snap_distance: f32,
+    /// Distance increments for snapping translations along each axis of the gizmo,
+    /// for grids with different spacing on different axes. Replaces
+    /// [`GizmoConfig::snap_distance`] for the X, Y and Z translation handles and planes.
+    /// Free translation in the view plane still uses [`GizmoConfig::snap_distance`].
+    ///
+    /// With [`GizmoOrientation::Local`], the increments are along the local axes of the gizmo.
+    pub snap_distances: Option<mint::Vector3<f32>>,
     /// Scale increment for snapping scalings.
     pub snap_scale: f32,
+    /// World space bounds that the gizmo can be translated within,
+    /// given as the minimum and maximum corners of a box.
+    ///
+    /// The bounds limit the position of the gizmo, not of the individual targets.
+    /// Does not move a gizmo that is already outside the bounds
+    /// until it is translated.
+    pub translation_bounds: Option<(mint::Vector3<f64>, mint::Vector3<f64>)>,
+    /// Minimum and maximum scale that scaling can result in, for example to keep
+    /// objects from collapsing to zero. Each dragged axis is clamped separately.
+    ///
+    /// The range applies to the scale of the gizmo, which is the average scale
+    /// of the targets. Does not change a scale that is already outside the range
+    /// until the axis is scaled. Negative scales are clamped by magnitude and keep
+    /// their sign.
+    ///
+    /// The bounds are swapped if given in the wrong order, and the lower bound
+    /// is kept at or above [`MIN_SCALE_CLAMP`].
+    pub scale_clamp: Option<(f32, f32)>,
+    /// Whether scaling can go past zero into negative scales, mirroring the targets.
+    /// Dragging a scale handle across the center of the gizmo flips the sign of the scale.
+    /// Zero scales are still avoided, to keep the transforms invertible.
+    pub allow_negative_scale: bool,
+    /// Restricts picking to the viewport, expanded by this margin on each side.
+    /// The margin is given in the same units as the viewport.
+    ///
+    /// With [`None`], the gizmo can be picked anywhere, including outside the viewport.
+    /// An ongoing drag is never restricted.
+    pub pick_margin: Option<f32>,
+    /// Whether the y coordinate of cursor positions grows downwards from the top of
+    /// the viewport. The draw data always uses a top-left origin.
+    pub viewport_y_down: bool,
+    /// Handedness of the coordinate system. Overrides the handedness inferred from
+    /// the projection matrix, which can be wrong for some custom projections,
+    /// such as reverse-Z projections. Inferred when `None`.
+    pub handedness: Option<Handedness>,
+    /// Multiplier for the distance within which the pointer grabs a subgizmo.
+    /// The distance is derived from [`GizmoVisuals::stroke_width`]. Increase this
+    /// to make the subgizmos easier to grab with touch input.
+    pub grab_radius_multiplier: f32,
+    /// How fast the gizmo moves relative to the pointer in precision mode.
+    /// See [`GizmoInteraction::precision`](crate::GizmoInteraction::precision).
+    pub precision_factor: f32,
+    /// How fast scaling reacts to the pointer motion. The change in scale given
+    /// by the pointer is multiplied by this, before any snapping. Values below 1.0
+    /// give finer control over the scale, values above 1.0 scale faster.
+    pub scale_sensitivity: f32,
+    /// Opacity of the whole gizmo, from 0.0 to 1.0, multiplying the colors of everything
+    /// that is drawn. At 0.0, nothing is drawn and the gizmo cannot be picked.
+    pub opacity: f32,
+    /// Whether the gizmo can be interacted with. When false, the gizmo is still drawn,
+    /// but it is never picked or dragged, for example to show a read-only transform.
+    pub interactive: bool,
+    /// Whether to add a dolly handle below the gizmo, for translating along the view
+    /// direction. Dragging the handle up moves away from the camera, and dragging it
+    /// down moves towards the camera. Only used with [`GizmoMode::Translate`].
+    ///
+    /// The handle is reported with [`GizmoDirection::View`], like the screen plane
+    /// handle, but with [`TransformKind::Axis`] instead of [`TransformKind::Plane`].
+    pub dolly_handle: bool,
+    /// Whether the size of the gizmo is fixed on the screen or in the world.
+    pub size_mode: SizeMode,
     /// Visual settings for the gizmo, affecting appearance and visibility.
     pub visuals: GizmoVisuals,
     /// Ratio of window's physical size to logical size.
+    ///
+    /// Only used for tessellating the gizmo shapes. The size of the gizmo is given in the
+    /// same units as the viewport, so with a viewport in logical pixels the gizmo keeps
+    /// its logical size when this changes, e.g. when moving the window to another monitor.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub pixels_per_point: f32,
 }
 
@@ -55,12 +269,33 @@ impl Default for GizmoConfig {
             projection_matrix: DMat4::IDENTITY.into(),
             viewport: Rect::NOTHING,
             modes: enum_set!(GizmoMode::Rotate),
+            enabled_directions: EnumSet::all(),
+            rotation_directions: EnumSet::all(),
+            planar_2d: false,
             orientation: GizmoOrientation::default(),
             pivot_point: TransformPivotPoint::default(),
+            rotation_mode: RotationInputMode::default(),
+            pivot: None,
+            offset: DVec3::ZERO.into(),
+            offset_orientation: GizmoOrientation::Global,
             snapping: false,
             snap_angle: DEFAULT_SNAP_ANGLE,
             snap_distance: DEFAULT_SNAP_DISTANCE,
+            snap_distances: None,
             snap_scale: DEFAULT_SNAP_SCALE,
+            translation_bounds: None,
+            scale_clamp: None,
+            allow_negative_scale: false,
+            pick_margin: None,
+            viewport_y_down: true,
+            handedness: None,
+            grab_radius_multiplier: 1.0,
+            precision_factor: DEFAULT_PRECISION_FACTOR,
+            scale_sensitivity: 1.0,
+            opacity: 1.0,
+            interactive: true,
+            dolly_handle: false,
+            size_mode: SizeMode::default(),
             visuals: GizmoVisuals::default(),
             pixels_per_point: 1.0,
         }
@@ -68,6 +303,177 @@ impl Default for GizmoConfig {
 }
 
 impl GizmoConfig {
+    /// Creates a configuration for a right-handed perspective camera
+    /// located at `eye` and looking at `target`.
+    ///
+    /// `fov_y` is the vertical field of view in radians. The aspect ratio is
+    /// calculated from the viewport. Other options are set to their defaults.
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::{DVec3, Pos2}, GizmoConfig, Rect};
+    /// let config = GizmoConfig::from_camera(
+    ///     DVec3::new(0.0, 2.0, 5.0),
+    ///     DVec3::ZERO,
+    ///     DVec3::Y,
+    ///     45f64.to_radians(),
+    ///     0.1,
+    ///     1000.0,
+    ///     Rect::from_min_max(Pos2::ZERO, Pos2::new(1280.0, 720.0)),
+    /// );
+    /// ```
+    pub fn from_camera(
+        eye: impl Into<mint::Vector3<f64>>,
+        target: impl Into<mint::Vector3<f64>>,
+        up: impl Into<mint::Vector3<f64>>,
+        fov_y: f64,
+        near: f64,
+        far: f64,
+        viewport: Rect,
+    ) -> Self {
+        let aspect_ratio = (viewport.width() / viewport.height()) as f64;
+
+        let view_matrix = DMat4::look_at_rh(
+            DVec3::from(eye.into()),
+            DVec3::from(target.into()),
+            DVec3::from(up.into()),
+        );
+        let projection_matrix = DMat4::perspective_rh(fov_y, aspect_ratio, near, far);
+
+        Self {
+            view_matrix: view_matrix.into(),
+            projection_matrix: projection_matrix.into(),
+            viewport,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a builder for a configuration, starting from the defaults.
+    ///
+    /// ```
+    /// # use transform_gizmo::prelude::*;
+    /// # use transform_gizmo::math::DMat4;
+    /// let config = GizmoConfig::builder()
+    ///     .view_matrix(DMat4::from_translation([0.0, 0.0, -5.0].into()))
+    ///     .modes(GizmoMode::Translate | GizmoMode::Rotate)
+    ///     .snapping(true)
+    ///     .snap_angle(15f32.to_radians())
+    ///     .build();
+    ///
+    /// assert!(config.snapping);
+    /// assert_eq!(config.modes, GizmoMode::Translate | GizmoMode::Rotate);
+    /// assert_eq!(config.snap_distance, GizmoConfig::default().snap_distance);
+    /// ```
+    pub fn builder() -> GizmoConfigBuilder {
+        GizmoConfigBuilder::default()
+    }
+
+    /// Sets the view matrix from a row-major matrix.
+    pub fn with_view_row_major(mut self, view_matrix: mint::RowMatrix4<f64>) -> Self {
+        self.view_matrix = view_matrix;
+        self
+    }
+
+    /// Sets the view matrix from a column-major matrix.
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::DMat4, GizmoConfig};
+    /// let view_matrix = DMat4::from_translation([1.0, 2.0, 3.0].into());
+    ///
+    /// let column_major = GizmoConfig::default().with_view_column_major(view_matrix.into());
+    /// let row_major = GizmoConfig::default().with_view_row_major(view_matrix.into());
+    ///
+    /// assert_eq!(column_major.view_matrix, row_major.view_matrix);
+    /// ```
+    pub fn with_view_column_major(mut self, view_matrix: mint::ColumnMatrix4<f64>) -> Self {
+        self.view_matrix = DMat4::from(view_matrix).into();
+        self
+    }
+
+    /// Sets the projection matrix from a row-major matrix.
+    pub fn with_projection_row_major(mut self, projection_matrix: mint::RowMatrix4<f64>) -> Self {
+        self.projection_matrix = projection_matrix;
+        self
+    }
+
+    /// Sets the projection matrix from a column-major matrix.
+    pub fn with_projection_column_major(
+        mut self,
+        projection_matrix: mint::ColumnMatrix4<f64>,
+    ) -> Self {
+        self.projection_matrix = DMat4::from(projection_matrix).into();
+        self
+    }
+
+    /// Sets [`GizmoVisuals::gizmo_size`] to the given fraction of the viewport height,
+    /// so that the gizmo scales with the viewport.
+    ///
+    /// The size is not updated automatically, so this should be called again
+    /// whenever the viewport changes. Does nothing if the viewport is not set.
+    ///
+    /// ```
+    /// # use transform_gizmo::{GizmoConfig, Rect};
+    /// # use transform_gizmo::math::Pos2;
+    /// let mut config = GizmoConfig {
+    ///     viewport: Rect::from_min_max(Pos2::ZERO, Pos2::new(1920.0, 1000.0)),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// config.set_relative_gizmo_size(0.15);
+    ///
+    /// assert_eq!(config.visuals.gizmo_size, 150.0);
+    /// ```
+    pub fn set_relative_gizmo_size(&mut self, fraction: f32) {
+        // The viewport is given in logical pixels, same as the gizmo size,
+        // so the size does not need to be scaled by `pixels_per_point`.
+        if self.viewport.is_finite() {
+            self.visuals.gizmo_size = self.viewport.height() * fraction;
+        }
+    }
+
+    /// Calculates a world space ray from a position on the screen, such as the cursor position.
+    /// The position is given in the same coordinates as [`GizmoInteraction::cursor_pos`](crate::GizmoInteraction::cursor_pos),
+    /// taking [`GizmoConfig::viewport_y_down`] into account.
+    ///
+    /// ```
+    /// # use transform_gizmo::math::{DVec3, Pos2, Rect};
+    /// # use transform_gizmo::GizmoConfig;
+    /// let config = GizmoConfig {
+    ///     viewport: Rect::from_min_max(Pos2::ZERO, Pos2::new(100.0, 100.0)),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let ray = config.ray_from_screen(Pos2::new(50.0, 50.0));
+    /// assert!((ray.direction.length() - 1.0).abs() < 1e-6);
+    /// assert!(ray.direction.abs_diff_eq(DVec3::Z, 1e-6));
+    /// ```
+    pub fn ray_from_screen(&self, pos: Pos2) -> Ray {
+        let screen_pos = self.cursor_to_screen(pos);
+        let view_projection = DMat4::from(self.projection_matrix) * DMat4::from(self.view_matrix);
+        let mat = view_projection.inverse();
+        let origin = screen_to_world(self.viewport, mat, screen_pos, -1.0);
+        let target = screen_to_world(self.viewport, mat, screen_pos, 1.0);
+
+        let direction = (target - origin).normalize();
+
+        Ray {
+            screen_pos,
+            origin,
+            direction,
+        }
+    }
+
+    /// Converts a cursor position to screen coordinates with a top-left origin.
+    pub(crate) fn cursor_to_screen(&self, cursor_pos: Pos2) -> Pos2 {
+        if self.viewport_y_down {
+            cursor_pos
+        } else {
+            Pos2::new(
+                cursor_pos.x,
+                self.viewport.min.y + self.viewport.max.y - cursor_pos.y,
+            )
+        }
+    }
+
     /// Forward vector of the view camera
     pub(crate) fn view_forward(&self) -> DVec3 {
         DVec4::from(self.view_matrix.z).xyz()
@@ -100,6 +506,225 @@ impl GizmoConfig {
     }
 }
 
+/// Builder for a [`GizmoConfig`], created with [`GizmoConfig::builder`].
+///
+/// Fields that are not set keep their default values.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct GizmoConfigBuilder {
+    config: GizmoConfig,
+}
+
+impl GizmoConfigBuilder {
+    /// Sets [`GizmoConfig::view_matrix`].
+    pub fn view_matrix(mut self, view_matrix: impl Into<mint::RowMatrix4<f64>>) -> Self {
+        self.config.view_matrix = view_matrix.into();
+        self
+    }
+
+    /// Sets [`GizmoConfig::projection_matrix`].
+    pub fn projection_matrix(
+        mut self,
+        projection_matrix: impl Into<mint::RowMatrix4<f64>>,
+    ) -> Self {
+        self.config.projection_matrix = projection_matrix.into();
+        self
+    }
+
+    /// Sets [`GizmoConfig::viewport`].
+    pub fn viewport(mut self, viewport: Rect) -> Self {
+        self.config.viewport = viewport;
+        self
+    }
+
+    /// Sets [`GizmoConfig::modes`].
+    pub fn modes(mut self, modes: EnumSet<GizmoMode>) -> Self {
+        self.config.modes = modes;
+        self
+    }
+
+    /// Sets [`GizmoConfig::enabled_directions`].
+    pub fn enabled_directions(mut self, enabled_directions: EnumSet<GizmoDirection>) -> Self {
+        self.config.enabled_directions = enabled_directions;
+        self
+    }
+
+    /// Sets [`GizmoConfig::rotation_directions`].
+    pub fn rotation_directions(mut self, rotation_directions: EnumSet<GizmoDirection>) -> Self {
+        self.config.rotation_directions = rotation_directions;
+        self
+    }
+
+    /// Sets [`GizmoConfig::planar_2d`].
+    pub fn planar_2d(mut self, planar_2d: bool) -> Self {
+        self.config.planar_2d = planar_2d;
+        self
+    }
+
+    /// Sets [`GizmoConfig::orientation`].
+    pub fn orientation(mut self, orientation: GizmoOrientation) -> Self {
+        self.config.orientation = orientation;
+        self
+    }
+
+    /// Sets [`GizmoConfig::rotation_mode`].
+    pub fn rotation_mode(mut self, rotation_mode: RotationInputMode) -> Self {
+        self.config.rotation_mode = rotation_mode;
+        self
+    }
+
+    /// Sets [`GizmoConfig::pivot_point`].
+    pub fn pivot_point(mut self, pivot_point: TransformPivotPoint) -> Self {
+        self.config.pivot_point = pivot_point;
+        self
+    }
+
+    /// Sets [`GizmoConfig::pivot`].
+    pub fn pivot(mut self, pivot: Option<mint::Vector3<f64>>) -> Self {
+        self.config.pivot = pivot;
+        self
+    }
+
+    /// Sets [`GizmoConfig::offset`].
+    pub fn offset(mut self, offset: mint::Vector3<f64>) -> Self {
+        self.config.offset = offset;
+        self
+    }
+
+    /// Sets [`GizmoConfig::offset_orientation`].
+    pub fn offset_orientation(mut self, offset_orientation: GizmoOrientation) -> Self {
+        self.config.offset_orientation = offset_orientation;
+        self
+    }
+
+    /// Sets [`GizmoConfig::snapping`].
+    pub fn snapping(mut self, snapping: bool) -> Self {
+        self.config.snapping = snapping;
+        self
+    }
+
+    /// Sets [`GizmoConfig::snap_angle`].
+    pub fn snap_angle(mut self, snap_angle: f32) -> Self {
+        self.config.snap_angle = snap_angle;
+        self
+    }
+
+    /// Sets [`GizmoConfig::snap_distance`].
+    pub fn snap_distance(mut self, snap_distance: f32) -> Self {
+        self.config.snap_distance = snap_distance;
+        self
+    }
+
+    /// Sets [`GizmoConfig::snap_distances`].
+    pub fn snap_distances(mut self, snap_distances: Option<mint::Vector3<f32>>) -> Self {
+        self.config.snap_distances = snap_distances;
+        self
+    }
+
+    /// Sets [`GizmoConfig::snap_scale`].
+    pub fn snap_scale(mut self, snap_scale: f32) -> Self {
+        self.config.snap_scale = snap_scale;
+        self
+    }
+
+    /// Sets [`GizmoConfig::translation_bounds`].
+    pub fn translation_bounds(
+        mut self,
+        translation_bounds: Option<(mint::Vector3<f64>, mint::Vector3<f64>)>,
+    ) -> Self {
+        self.config.translation_bounds = translation_bounds;
+        self
+    }
+
+    /// Sets [`GizmoConfig::scale_clamp`].
+    pub fn scale_clamp(mut self, scale_clamp: Option<(f32, f32)>) -> Self {
+        self.config.scale_clamp = scale_clamp;
+        self
+    }
+
+    /// Sets [`GizmoConfig::allow_negative_scale`].
+    pub fn allow_negative_scale(mut self, allow_negative_scale: bool) -> Self {
+        self.config.allow_negative_scale = allow_negative_scale;
+        self
+    }
+
+    /// Sets [`GizmoConfig::pick_margin`].
+    pub fn pick_margin(mut self, pick_margin: Option<f32>) -> Self {
+        self.config.pick_margin = pick_margin;
+        self
+    }
+
+    /// Sets [`GizmoConfig::viewport_y_down`].
+    pub fn viewport_y_down(mut self, viewport_y_down: bool) -> Self {
+        self.config.viewport_y_down = viewport_y_down;
+        self
+    }
+
+    /// Sets [`GizmoConfig::handedness`].
+    pub fn handedness(mut self, handedness: Option<Handedness>) -> Self {
+        self.config.handedness = handedness;
+        self
+    }
+
+    /// Sets [`GizmoConfig::grab_radius_multiplier`].
+    pub fn grab_radius_multiplier(mut self, grab_radius_multiplier: f32) -> Self {
+        self.config.grab_radius_multiplier = grab_radius_multiplier;
+        self
+    }
+
+    /// Sets [`GizmoConfig::precision_factor`].
+    pub fn precision_factor(mut self, precision_factor: f32) -> Self {
+        self.config.precision_factor = precision_factor;
+        self
+    }
+
+    /// Sets [`GizmoConfig::scale_sensitivity`].
+    pub fn scale_sensitivity(mut self, scale_sensitivity: f32) -> Self {
+        self.config.scale_sensitivity = scale_sensitivity;
+        self
+    }
+
+    /// Sets [`GizmoConfig::opacity`].
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.config.opacity = opacity;
+        self
+    }
+
+    /// Sets [`GizmoConfig::interactive`].
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.config.interactive = interactive;
+        self
+    }
+
+    /// Sets [`GizmoConfig::dolly_handle`].
+    pub fn dolly_handle(mut self, dolly_handle: bool) -> Self {
+        self.config.dolly_handle = dolly_handle;
+        self
+    }
+
+    /// Sets [`GizmoConfig::size_mode`].
+    pub fn size_mode(mut self, size_mode: SizeMode) -> Self {
+        self.config.size_mode = size_mode;
+        self
+    }
+
+    /// Sets [`GizmoConfig::visuals`].
+    pub fn visuals(mut self, visuals: GizmoVisuals) -> Self {
+        self.config.visuals = visuals;
+        self
+    }
+
+    /// Sets [`GizmoConfig::pixels_per_point`].
+    pub fn pixels_per_point(mut self, pixels_per_point: f32) -> Self {
+        self.config.pixels_per_point = pixels_per_point;
+        self
+    }
+
+    /// Returns the built configuration.
+    pub fn build(self) -> GizmoConfig {
+        self.config
+    }
+}
+
 #[derive(Debug, Copy, Clone, Default)]
 pub(crate) struct PreparedGizmoConfig {
     config: GizmoConfig,
@@ -115,14 +740,30 @@ pub(crate) struct PreparedGizmoConfig {
     pub(crate) model_matrix: DMat4,
     /// Combined model-view-projection matrix
     pub(crate) mvp: DMat4,
-    /// Scale factor for the gizmo rendering
+    /// World space size of a unit of [`GizmoVisuals::gizmo_size`] at the gizmo
     pub(crate) scale_factor: f32,
+    /// World space size of a pixel at the gizmo
+    pub(crate) pixel_size: f32,
+    /// World space size of a unit of the sizes given in pixels, such as stroke widths.
+    /// Follows the idle size of the gizmo like [`PreparedGizmoConfig::scale_factor`].
+    pub(crate) pixel_scale_factor: f32,
     /// How close the mouse pointer needs to be to a subgizmo before it is focused
     pub(crate) focus_distance: f32,
     /// Whether left-handed projection is used
     pub(crate) left_handed: bool,
     /// Direction from the camera to the gizmo in world space
     pub(crate) eye_to_model_dir: DVec3,
+    /// Whether the view-projection matrix can be inverted
+    pub(crate) invertible: bool,
+    /// Whether precision mode is active this frame
+    pub(crate) precision: bool,
+    /// Whether snapping is temporarily toggled this frame
+    pub(crate) snap_toggle: bool,
+    /// Axis or plane that the free translation is locked to during the current drag
+    pub(crate) axis_lock: Option<(GizmoDirection, TransformKind)>,
+    /// How far the gizmo has shrunk towards [`GizmoVisuals::idle_size_scale`],
+    /// from 0.0 at full size to 1.0 at the idle size
+    pub(crate) idle_amount: f32,
 }
 
 impl Deref for PreparedGizmoConfig {
@@ -146,15 +787,26 @@ impl PreparedGizmoConfig {
 
         let view_projection = projection_matrix * view_matrix;
 
-        let left_handed = if projection_matrix.z_axis.w == 0.0 {
-            projection_matrix.z_axis.z > 0.0
-        } else {
-            projection_matrix.z_axis.w > 0.0
+        let left_handed = match config.handedness {
+            Some(handedness) => handedness == Handedness::Left,
+            None if projection_matrix.z_axis.w == 0.0 => projection_matrix.z_axis.z > 0.0,
+            None => projection_matrix.z_axis.w > 0.0,
         };
 
         self.config = config;
+        self.config.snap_angle = sanitize_snap_angle(config.snap_angle);
+        self.config.scale_clamp = config.scale_clamp.map(sanitize_scale_clamp);
+        self.config.opacity = if config.opacity.is_nan() {
+            1.0
+        } else {
+            config.opacity.clamp(0.0, 1.0)
+        };
+        self.config.visuals.circle_segments =
+            config.visuals.circle_segments.max(MIN_CIRCLE_SEGMENTS);
         self.view_projection = view_projection;
         self.left_handed = left_handed;
+        self.invertible =
+            view_projection.determinant().is_normal() && view_projection.inverse().is_finite();
 
         self.update_transform(Transform {
             scale: self.scale.into(),
@@ -163,27 +815,55 @@ impl PreparedGizmoConfig {
         });
     }
 
-    pub(crate) fn update_for_targets(&mut self, targets: &[Transform]) {
+    /// Places the gizmo based on the targets. The translation is the custom pivot
+    /// if there is one, the translation of the last target with
+    /// [`TransformPivotPoint::ActiveElement`], and otherwise a weighted mean of the
+    /// target translations. Uniform weights are used if the number of weights does
+    /// not match the number of targets. The scale and rotation are averaged from
+    /// the targets.
+    pub(crate) fn update_for_targets(&mut self, targets: &[Transform], weights: &[f64]) {
         let mut scale = DVec3::ZERO;
         let mut translation = DVec3::ZERO;
-        let mut rotation = DQuat::IDENTITY;
+
+        let weights = (weights.len() == targets.len() && weights.iter().sum::<f64>() > 0.0)
+            .then_some(weights);
 
         let mut target_count = 0;
-        for target in targets {
+        let mut total_weight = 0.0;
+        for (i, target) in targets.iter().enumerate() {
+            let weight = weights.map_or(1.0, |weights| weights[i]);
+
             scale += DVec3::from(target.scale);
-            translation += DVec3::from(target.translation);
-            rotation = DQuat::from(target.rotation);
+            translation += DVec3::from(target.translation) * weight;
 
             target_count += 1;
+            total_weight += weight;
         }
 
         if target_count == 0 {
             scale = DVec3::ONE;
         } else {
-            translation /= target_count as f64;
+            translation /= total_weight;
             scale /= target_count as f64;
         }
 
+        let rotation = average_rotation(targets);
+
+        if let Some(pivot) = self.config.pivot {
+            translation = pivot.into();
+        } else {
+            if let (TransformPivotPoint::ActiveElement, Some(active)) =
+                (self.config.pivot_point, targets.last())
+            {
+                translation = active.translation.into();
+            }
+
+            translation += match self.config.offset_orientation {
+                GizmoOrientation::Global => DVec3::from(self.config.offset),
+                GizmoOrientation::Local => rotation * DVec3::from(self.config.offset),
+            };
+        }
+
         self.update_transform(Transform {
             scale: scale.into(),
             rotation: rotation.into(),
@@ -199,11 +879,41 @@ impl PreparedGizmoConfig {
             DMat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation);
         self.mvp = self.view_projection * self.model_matrix;
 
-        self.scale_factor = self.mvp.as_ref()[15] as f32
+        if !self.is_valid() {
+            // The gizmo cannot be placed on the screen. Leave it inert
+            // instead of filling the values below with garbage.
+            self.scale_factor = 0.0;
+            self.pixel_size = 0.0;
+            self.pixel_scale_factor = 0.0;
+            self.focus_distance = 0.0;
+            self.eye_to_model_dir = DVec3::ZERO;
+            return;
+        }
+
+        // World space size of a pixel at the gizmo. The w of the gizmo origin in clip space
+        // is its view depth under a perspective projection and 1.0 under an orthographic one,
+        // so this gives the correct size for both kinds of projections.
+        let pixel_size = self.mvp.as_ref()[15] as f32
             / self.projection_matrix.x.x as f32
             / self.config.viewport.width()
             * 2.0;
 
+        let size_factor = match self.config.size_mode {
+            SizeMode::ScreenPixels => pixel_size,
+            SizeMode::WorldUnits(size) => {
+                let factor = size as f32 / self.config.visuals.gizmo_size;
+                if factor.is_finite() {
+                    factor
+                } else {
+                    0.0
+                }
+            }
+        };
+
+        self.scale_factor = size_factor * self.size_scale();
+        self.pixel_size = pixel_size;
+        self.pixel_scale_factor = pixel_size * self.size_scale();
+
         let gizmo_screen_pos =
             world_to_screen(self.config.viewport, self.mvp, self.translation).unwrap_or_default();
 
@@ -214,11 +924,126 @@ impl PreparedGizmoConfig {
             -1.0,
         );
 
-        self.focus_distance = self.scale_factor * (self.config.visuals.stroke_width / 2.0 + 5.0);
+        self.focus_distance = pixel_size
+            * (self.config.visuals.stroke_width / 2.0 + 5.0)
+            * self.config.grab_radius_multiplier.max(0.0);
 
         self.eye_to_model_dir = (gizmo_view_near - self.translation).normalize_or_zero();
     }
 
+    /// Alpha multiplier of the gizmo colors from [`GizmoVisuals::distance_fade`],
+    /// based on the view space depth of the gizmo.
+    #[cfg(feature = "draw")]
+    pub(crate) fn distance_fade(&self) -> f32 {
+        let Some((start, end)) = self.config.visuals.distance_fade else {
+            return 1.0;
+        };
+
+        let depth = DMat4::from(self.config.view_matrix)
+            .transform_point3(self.translation)
+            .z
+            .abs() as f32;
+
+        if depth <= start {
+            1.0
+        } else if depth >= end {
+            0.0
+        } else {
+            1.0 - (depth - start) / (end - start)
+        }
+    }
+
+    /// Size multiplier of the gizmo, shrinking it while it is idle.
+    fn size_scale(&self) -> f32 {
+        1.0 + (self.config.visuals.idle_size_scale - 1.0) * self.idle_amount
+    }
+
+    /// Animates the size of the gizmo towards full size when the pointer is
+    /// near it or the gizmo is being interacted with, and towards the idle size otherwise.
+    pub(crate) fn update_idle_amount(&mut self, screen_pos: Pos2, active: bool) {
+        let near = active
+            || world_to_screen(self.config.viewport, self.view_projection, self.translation)
+                .is_some_and(|gizmo_pos| {
+                    gizmo_pos.distance(screen_pos) < self.config.visuals.gizmo_size * IDLE_PROXIMITY
+                });
+
+        let target = if near { 0.0 } else { 1.0 };
+        let smoothing = self.config.visuals.idle_size_smoothing.clamp(0.0, 1.0);
+        self.idle_amount += (target - self.idle_amount) * smoothing;
+
+        self.update_transform(self.as_transform());
+    }
+
+    /// Distance increment for snapping translations along the given local axis of the gizmo.
+    /// With a zero axis, or without per-axis increments, [`GizmoConfig::snap_distance`] is used.
+    pub(crate) fn snap_distance_along(&self, local_axis: DVec3) -> f64 {
+        match self.config.snap_distances {
+            Some(distances) if local_axis != DVec3::ZERO => {
+                DVec3::new(distances.x as f64, distances.y as f64, distances.z as f64)
+                    .dot(local_axis.abs())
+            }
+            _ => self.config.snap_distance as f64,
+        }
+    }
+
+    /// Whether snapping is in effect this frame, taking the temporary toggle into account
+    pub(crate) fn is_snapping(&self) -> bool {
+        self.config.snapping != self.snap_toggle
+    }
+
+    /// Whether the gizmo can be picked at the given screen position.
+    pub(crate) fn is_pickable(&self, screen_pos: Pos2) -> bool {
+        self.config.interactive
+            && self.config.opacity > 0.0
+            && self.config.pick_margin.map_or(true, |margin| {
+                self.config.viewport.expand(margin).contains(screen_pos)
+            })
+    }
+
+    /// Updates the axis lock after an axis key was pressed during a drag.
+    /// Pressing the key of a new axis locks to that axis, pressing it again
+    /// locks to the plane perpendicular to it, and a third press unlocks.
+    /// [`GizmoDirection::View`] unlocks. With [`GizmoConfig::planar_2d`], locks that
+    /// would leave the XY plane are skipped, so Z locks to the XY plane.
+    pub(crate) fn toggle_axis_lock(&mut self, direction: GizmoDirection) {
+        let axis_lock = match self.axis_lock {
+            _ if direction == GizmoDirection::View => None,
+            Some((locked, TransformKind::Axis)) if locked == direction => {
+                Some((direction, TransformKind::Plane))
+            }
+            Some((locked, TransformKind::Plane)) if locked == direction => None,
+            _ => Some((direction, TransformKind::Axis)),
+        };
+
+        if !self.config.planar_2d {
+            self.axis_lock = axis_lock;
+            return;
+        }
+
+        self.axis_lock = match axis_lock {
+            Some((GizmoDirection::Z, TransformKind::Axis)) => {
+                Some((GizmoDirection::Z, TransformKind::Plane))
+            }
+            Some((GizmoDirection::X | GizmoDirection::Y, TransformKind::Plane)) => None,
+            axis_lock => axis_lock,
+        };
+    }
+
+    /// Scale applied to the pointer motion during interaction.
+    pub(crate) fn motion_scale(&self) -> f64 {
+        if self.precision {
+            self.config.precision_factor as f64
+        } else {
+            1.0
+        }
+    }
+
+    /// Whether the gizmo can be interacted with and drawn using this configuration.
+    /// Requires a finite viewport and an invertible view-projection matrix.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.config.viewport.is_finite() && self.invertible
+    }
+
     pub(crate) fn as_transform(&self) -> Transform {
         Transform {
             scale: self.scale.into(),
@@ -228,35 +1053,208 @@ impl PreparedGizmoConfig {
     }
 }
 
+/// Averages the target rotations by summing them and normalizing the sum.
+/// Each rotation is flipped to the hemisphere with a non-negative `w` before
+/// summing, since `q` and `-q` represent the same rotation. Returns the identity
+/// if there are no targets or the rotations cancel each other out.
+fn average_rotation(targets: &[Transform]) -> DQuat {
+    let sum = targets
+        .iter()
+        .fold(DQuat::from_xyzw(0.0, 0.0, 0.0, 0.0), |sum, target| {
+            let rotation = DQuat::from(target.rotation);
+            if rotation.w < 0.0 {
+                sum + -rotation
+            } else {
+                sum + rotation
+            }
+        });
+
+    if sum.length_squared() > 1e-12 {
+        sum.normalize()
+    } else {
+        DQuat::IDENTITY
+    }
+}
+
+/// Clamps the snapping angle to a range that can be safely used
+/// for snapping and for drawing the snapping ticks.
+fn sanitize_snap_angle(snap_angle: f32) -> f32 {
+    if snap_angle.is_finite() {
+        snap_angle
+            .abs()
+            .clamp(MIN_SNAP_ANGLE, std::f32::consts::TAU)
+    } else {
+        DEFAULT_SNAP_ANGLE
+    }
+}
+
+/// Orders the scale range and keeps its lower bound above zero.
+/// A NaN lower bound is raised to the smallest allowed bound and
+/// a NaN upper bound leaves the range unbounded from above.
+fn sanitize_scale_clamp((min, max): (f32, f32)) -> (f32, f32) {
+    let (min, max) = if min > max { (max, min) } else { (min, max) };
+    let min = min.max(MIN_SCALE_CLAMP);
+    let max = if max.is_nan() {
+        f32::INFINITY
+    } else {
+        max.max(min)
+    };
+    (min, max)
+}
+
+/// Custom rounding function used for snapping.
+///
+/// See [`Gizmo::set_snap_fn`](crate::Gizmo::set_snap_fn).
+pub type SnapFn = Arc<dyn Fn(f64) -> f64 + Send + Sync>;
+
+/// Custom rounding functions used for snapping in each mode.
+#[derive(Clone, Default)]
+pub(crate) struct SnapFunctions {
+    rotate: Option<SnapFn>,
+    translate: Option<SnapFn>,
+    scale: Option<SnapFn>,
+    /// Scales that scaling snaps to when close enough
+    scale_targets: Vec<f64>,
+    /// How close the scale needs to be to a target to snap to it
+    scale_target_tolerance: f64,
+}
+
+impl SnapFunctions {
+    pub(crate) fn get(&self, mode: GizmoMode) -> Option<&SnapFn> {
+        match mode {
+            GizmoMode::Rotate => self.rotate.as_ref(),
+            GizmoMode::Translate => self.translate.as_ref(),
+            GizmoMode::Scale => self.scale.as_ref(),
+        }
+    }
+
+    pub(crate) fn set(&mut self, mode: GizmoMode, snap_fn: Option<SnapFn>) {
+        match mode {
+            GizmoMode::Rotate => self.rotate = snap_fn,
+            GizmoMode::Translate => self.translate = snap_fn,
+            GizmoMode::Scale => self.scale = snap_fn,
+        }
+    }
+
+    pub(crate) fn set_scale_targets(&mut self, targets: Vec<f64>, tolerance: f64) {
+        self.scale_targets = targets;
+        self.scale_target_tolerance = tolerance;
+    }
+
+    /// Finds the scale target closest to the given scale, if any are within the tolerance.
+    pub(crate) fn snap_scale_to_target(&self, scale: f64) -> Option<f64> {
+        self.scale_targets
+            .iter()
+            .copied()
+            .filter(|target| (target - scale).abs() <= self.scale_target_tolerance)
+            .min_by(|a, b| (a - scale).abs().total_cmp(&(b - scale).abs()))
+    }
+
+    /// Snaps the value using the custom function of the given mode.
+    /// If there is none, the value is rounded to the nearest interval.
+    pub(crate) fn snap(&self, mode: GizmoMode, value: f64, interval: f64) -> f64 {
+        match self.get(mode) {
+            Some(snap_fn) => snap_fn(value),
+            None => round_to_interval(value, interval),
+        }
+    }
+}
+
+impl Debug for SnapFunctions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapFunctions")
+            .field("rotate", &self.rotate.is_some())
+            .field("translate", &self.translate.is_some())
+            .field("scale", &self.scale.is_some())
+            .field("scale_targets", &self.scale_targets)
+            .field("scale_target_tolerance", &self.scale_target_tolerance)
+            .finish()
+    }
+}
+
 /// Operation mode of a gizmo.
 #[derive(Debug, EnumSetType)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GizmoMode {
     Rotate,
     Translate,
     Scale,
 }
 
-/// The point in space around which all rotations are centered.
+/// The point in space around which all rotations and scalings are centered.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransformPivotPoint {
     /// Pivot around the median point of targets
     #[default]
     MedianPoint,
     /// Pivot around each target's own origin
     IndividualOrigins,
+    /// Pivot around the origin of the active target, which is the last of the targets
+    ActiveElement,
+}
+
+/// How cursor movement is turned into rotation when dragging a rotation ring.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RotationInputMode {
+    /// Rotate by the change in the angle from the center of the gizmo to the cursor.
+    /// Precise far from the center, but sensitive when the cursor is near it.
+    #[default]
+    Angle,
+    /// Rotate by the cursor movement along the ring at the point where it was grabbed,
+    /// as if the ring was dragged directly. Moving the cursor by the radius of the
+    /// ring on the screen rotates by one radian.
+    Tangent,
+}
+
+/// How the size of a gizmo is determined.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SizeMode {
+    /// [`GizmoVisuals::gizmo_size`] is in pixels, keeping the size of the
+    /// gizmo on the screen constant regardless of its distance from the camera.
+    #[default]
+    ScreenPixels,
+    /// The gizmo is sized in world units, so that [`GizmoVisuals::gizmo_size`] spans the
+    /// given world space distance. Stroke widths, arrow tips, margins and picking
+    /// tolerances stay in pixels.
+    WorldUnits(f64),
 }
 
 /// Orientation of a gizmo.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GizmoOrientation {
     /// Transformation axes are aligned to world space.
     #[default]
     Global,
-    /// Transformation axes are aligned to the last target's orientation.
+    /// Transformation axes are aligned to the average orientation of the targets.
     Local,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+/// Handedness of a coordinate system.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Handedness {
+    /// Left-handed coordinate system
+    Left,
+    /// Right-handed coordinate system
+    Right,
+}
+
+/// Kind of constraint a subgizmo transforms with.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransformKind {
+    /// Transforms along an axis, or around it for rotations
+    Axis,
+    /// Transforms on a plane, or freely for arcball rotations
+    Plane,
+}
+
+#[derive(Debug, EnumSetType, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GizmoDirection {
     /// Gizmo points in the X-direction
     X,
@@ -268,8 +1266,77 @@ pub enum GizmoDirection {
     View,
 }
 
+/// How the color of highlighted and active axes is chosen.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HighlightMode {
+    /// The axis color is replaced with [`GizmoVisuals::highlight_color`], if it is set.
+    #[default]
+    ReplaceColor,
+    /// The axis color is kept, but brightened.
+    /// [`GizmoVisuals::highlight_color`] is ignored.
+    BrightenAxisColor,
+}
+
+/// Shape of the handle at the end of a translation or scale axis.
+///
+/// When both translation and scaling are enabled, the translation handles are placed
+/// beyond the scale handles, so their pick regions never overlap. Using different
+/// shapes for the two modes tells them apart at a glance.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandleShape {
+    /// Arrow head pointing along the axis
+    Arrow,
+    /// Square block at the end of the axis
+    Square,
+    /// Round dot at the end of the axis
+    Circle,
+}
+
+/// How the planes of translation and scale are filled.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlaneFill {
+    /// The planes are filled with a solid color
+    #[default]
+    Solid,
+    /// The planes are outlined and filled with diagonal hatch lines
+    Hatched,
+}
+
+/// Unit of the angles given by [`GizmoResult::delta_angle`](crate::GizmoResult::delta_angle)
+/// and [`GizmoResult::total_angle`](crate::GizmoResult::total_angle).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AngleUnit {
+    /// Angles are given in degrees
+    #[default]
+    Degrees,
+    /// Angles are given in radians
+    Radians,
+}
+
+impl AngleUnit {
+    /// Converts an angle in radians to this unit.
+    ///
+    /// ```
+    /// # use transform_gizmo::config::AngleUnit;
+    /// assert_eq!(AngleUnit::Degrees.convert(std::f64::consts::PI), 180.0);
+    /// assert_eq!(AngleUnit::Radians.convert(1.5), 1.5);
+    /// ```
+    pub fn convert(self, radians: f64) -> f64 {
+        match self {
+            Self::Degrees => radians.to_degrees(),
+            Self::Radians => radians,
+        }
+    }
+}
+
 /// Controls the visual style of the gizmo
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct GizmoVisuals {
     /// Color of the x axis
     pub x_color: Color32,
@@ -285,10 +1352,83 @@ pub struct GizmoVisuals {
     pub highlight_alpha: f32,
     /// Color to use for highlighted and active axes. By default, the axis color is used with `highlight_alpha`
     pub highlight_color: Option<Color32>,
+    /// How the color of highlighted and active axes is chosen
+    pub highlight_mode: HighlightMode,
+    /// Shape of the translation axis handles
+    pub translate_handle: HandleShape,
+    /// Shape of the scale axis handles
+    pub scale_handle: HandleShape,
+    /// How the translation and scale planes are filled
+    pub plane_fill: PlaneFill,
     /// Width (thickness) of the gizmo strokes
     pub stroke_width: f32,
-    /// Gizmo size in pixels
+    /// Color of guides, such as the snapping grid
+    pub guide_color: Color32,
+    /// Width (thickness) of the guide strokes
+    pub guide_stroke_width: f32,
+    /// Gizmo size in pixels, in the same units as [`GizmoConfig::viewport`]
     pub gizmo_size: f32,
+    /// Radius of the inner circle relative to [`GizmoVisuals::gizmo_size`]. The translation
+    /// and scale arrows start at the inner circle, and the view plane handles are drawn on it.
+    pub inner_circle_radius_factor: f32,
+    /// Distance in pixels between the edge of the gizmo and the outer circle, in addition to
+    /// [`GizmoVisuals::stroke_width`]. The outer circle is used for the view axis rotation ring
+    /// and the uniform scale handle.
+    pub outer_circle_margin: f32,
+    /// Number of segments that a full circle is drawn with. Arcs use a proportional
+    /// share of the segments. Increase this for smoother rotation rings on large
+    /// viewports, or decrease it to draw the gizmo faster.
+    /// Clamped to at least [`MIN_CIRCLE_SEGMENTS`].
+    pub circle_segments: usize,
+    /// Whether to draw rings visualizing the yaw, pitch and roll decomposition
+    /// of the rotation. Useful for illustrating gimbal lock. The rings cannot be interacted with.
+    pub gimbal_rings: bool,
+    /// Whether to draw a grid on the active translation plane while snapping.
+    /// The grid lines show the positions that the gizmo snaps to.
+    pub snap_grid: bool,
+    /// Whether to also draw the translation and scale arrows along the negative axes.
+    /// The mirrored arrows can be dragged just like the regular ones.
+    pub mirrored_arrows: bool,
+    /// Whether to draw the axis letters X, Y and Z at the tips of the translation
+    /// and scale arrows. The labels fade out together with their arrows.
+    pub show_axis_labels: bool,
+    /// Whether to draw the scale handles along the negative axes as well.
+    /// Unlike [`GizmoVisuals::mirrored_arrows`], this is only visual, and the
+    /// mirrored handles cannot be dragged. Scaling is always symmetric about the origin.
+    pub mirrored_scale_handles: bool,
+    /// Minimum length of the translation and scale arrow shafts on the screen, in pixels.
+    /// Arrows that point almost towards the camera are extended to keep them grabbable.
+    /// Disabled when `None`.
+    pub min_arrow_screen_length: Option<f32>,
+    /// Length in pixels of the dashes, and the gaps between them, that the shafts of
+    /// translation and scale arrows pointing away from the camera are drawn with.
+    /// Helps to tell which side of the gizmo faces the camera. Only affects drawing.
+    /// Arrows are always drawn solid when `None`.
+    pub far_arrow_dash_length: Option<f32>,
+    /// Whether to outline the translation and scale planes while they are hovered,
+    /// making the clickable area of the planes easier to see.
+    pub plane_hover_border: bool,
+    /// Size of the gizmo relative to [`GizmoVisuals::gizmo_size`] while the pointer
+    /// is not near it. The gizmo grows back to full size as the pointer approaches.
+    /// The default of 1.0 keeps the gizmo at full size.
+    pub idle_size_scale: f32,
+    /// Fraction of the remaining size change between idle and full size that is
+    /// applied on each update, between 0.0 and 1.0. Smaller values animate more slowly.
+    pub idle_size_smoothing: f32,
+    /// Whether to hide all other subgizmos while one of them is being dragged.
+    pub hide_inactive_during_drag: bool,
+    /// Whether to draw the rotation arcs with a brightness gradient, fading out
+    /// towards the leading edge of a positive rotation around the axis of the arc.
+    pub rotation_arc_gradient: bool,
+    /// Height of a horizontal ground plane. When set, the position of the gizmo
+    /// is projected onto the ground plane and marked with a faint shadow,
+    /// connected to the gizmo with a vertical line.
+    pub ground_height: Option<f32>,
+    /// View space depths, as `(start, end)`, over which the gizmo fades out as it gets
+    /// farther from the camera. The gizmo is fully visible up to the start depth, and
+    /// fully transparent beyond the end depth. Picking is not affected.
+    /// Separate from the fading of handles that are viewed edge-on.
+    pub distance_fade: Option<(f32, f32)>,
 }
 
 impl Default for GizmoVisuals {
@@ -301,8 +1441,64 @@ impl Default for GizmoVisuals {
             inactive_alpha: 0.7,
             highlight_alpha: 1.0,
             highlight_color: None,
+            highlight_mode: HighlightMode::default(),
+            translate_handle: HandleShape::Arrow,
+            scale_handle: HandleShape::Square,
+            plane_fill: PlaneFill::default(),
             stroke_width: 4.0,
+            guide_color: Color32::from_gray(160),
+            guide_stroke_width: 1.5,
             gizmo_size: 75.0,
+            inner_circle_radius_factor: 0.2,
+            outer_circle_margin: 5.0,
+            circle_segments: DEFAULT_CIRCLE_SEGMENTS,
+            gimbal_rings: false,
+            snap_grid: false,
+            mirrored_arrows: false,
+            show_axis_labels: false,
+            mirrored_scale_handles: false,
+            min_arrow_screen_length: None,
+            far_arrow_dash_length: None,
+            plane_hover_border: false,
+            idle_size_scale: 1.0,
+            idle_size_smoothing: 0.2,
+            hide_inactive_during_drag: true,
+            rotation_arc_gradient: false,
+            ground_height: None,
+            distance_fade: None,
+        }
+    }
+}
+
+impl GizmoVisuals {
+    /// Color of the subgizmos of the given direction.
+    /// [`GizmoDirection::View`] uses [`GizmoVisuals::s_color`].
+    pub fn axis_color(&self, direction: GizmoDirection) -> Color32 {
+        match direction {
+            GizmoDirection::X => self.x_color,
+            GizmoDirection::Y => self.y_color,
+            GizmoDirection::Z => self.z_color,
+            GizmoDirection::View => self.s_color,
+        }
+    }
+
+    /// Sets the color of the subgizmos of the given direction.
+    /// [`GizmoDirection::View`] sets [`GizmoVisuals::s_color`].
+    ///
+    /// ```
+    /// # use transform_gizmo::{Color32, GizmoDirection, GizmoVisuals};
+    /// let mut visuals = GizmoVisuals::default();
+    /// visuals.set_axis_color(GizmoDirection::View, Color32::YELLOW);
+    ///
+    /// assert_eq!(visuals.s_color, Color32::YELLOW);
+    /// assert_eq!(visuals.axis_color(GizmoDirection::View), Color32::YELLOW);
+    /// ```
+    pub fn set_axis_color(&mut self, direction: GizmoDirection, color: Color32) {
+        match direction {
+            GizmoDirection::X => self.x_color = color,
+            GizmoDirection::Y => self.y_color = color,
+            GizmoDirection::Z => self.z_color = color,
+            GizmoDirection::View => self.s_color = color,
         }
     }
 }
@@ -1,21 +1,33 @@
-use ecolor::Rgba;
-use emath::Pos2;
-use std::ops::{Add, AddAssign, Sub};
+use ecolor::{Color32, Rgba};
+use emath::{Pos2, Rect};
+use std::fmt::Write;
+use std::ops::{Add, AddAssign};
+use std::sync::Arc;
+#[cfg(feature = "draw")]
+use std::sync::OnceLock;
+
+use enumset::EnumSet;
 
 use crate::config::{
-    GizmoConfig, GizmoDirection, GizmoMode, PreparedGizmoConfig, TransformPivotPoint,
+    AngleUnit, GizmoConfig, GizmoDirection, GizmoMode, PreparedGizmoConfig, SnapFn, SnapFunctions,
+    TransformPivotPoint,
 };
-use crate::math::{screen_to_world, Transform};
+use crate::math::{world_to_screen, Transform};
 use crate::GizmoOrientation;
+#[cfg(feature = "draw")]
 use epaint::Mesh;
-use glam::{DQuat, DVec3};
+use glam::{DMat4, DQuat, DVec3};
 
 use crate::subgizmo::rotation::RotationParams;
+use crate::subgizmo::scale::clamp_scale;
 use crate::subgizmo::scale::ScaleParams;
 use crate::subgizmo::translation::TranslationParams;
+#[cfg(feature = "draw")]
+use crate::subgizmo::{common::draw_ground_shadow, rotation::draw_gimbal_rings, SubGizmoDraw};
 use crate::subgizmo::{
-    common::TransformKind, ArcballSubGizmo, RotationSubGizmo, ScaleSubGizmo, SubGizmo,
-    SubGizmoControl, TranslationSubGizmo,
+    common::{gizmo_normal, outer_circle_radius, TransformKind},
+    ArcballSubGizmo, RotationSubGizmo, ScaleSubGizmo, SubGizmo, SubGizmoControl,
+    TranslationSubGizmo,
 };
 
 /// A 3D transformation gizmo.
@@ -26,6 +38,8 @@ pub struct Gizmo {
     /// various other values calculated from it, used for
     /// interaction and drawing the gizmo.
     config: PreparedGizmoConfig,
+    /// Configuration as last given to [`Gizmo::update_config`], before it was sanitized.
+    raw_config: GizmoConfig,
     /// Subgizmos used in the gizmo.
     subgizmos: Vec<SubGizmo>,
     active_subgizmo_id: Option<u64>,
@@ -33,6 +47,39 @@ pub struct Gizmo {
     target_start_transforms: Vec<Transform>,
 
     gizmo_start_transform: Transform,
+
+    /// Custom snapping functions, if any.
+    snap_fns: SnapFunctions,
+
+    /// Weights of the targets when placing the gizmo.
+    target_weights: Vec<f64>,
+
+    /// Whether a drag started in the latest update.
+    drag_started: bool,
+    /// Whether a drag finished in the latest update.
+    drag_finished: bool,
+    /// Whether a drag was cancelled in the latest update.
+    drag_cancelled: bool,
+    /// Latest result of the ongoing drag, used for reverting it when cancelled.
+    last_result: Option<GizmoResult>,
+    /// Uniform scale of the ongoing pinch gesture before snapping, if any.
+    pinch_scale: Option<f64>,
+    /// Whether the ongoing pinch gesture was cancelled.
+    pinch_cancelled: bool,
+    /// Draw data from the latest [`Gizmo::draw`] call, reused until
+    /// something that affects drawing changes.
+    #[cfg(feature = "draw")]
+    draw_cache: OnceLock<GizmoDrawData>,
+}
+
+/// State of the gizmo that the draw data depends on, besides the configuration.
+/// The draw data is regenerated when this changes in an update.
+#[derive(PartialEq)]
+struct DrawState {
+    transform: Transform,
+    idle_amount: f32,
+    focused_subgizmo_id: Option<u64>,
+    active_subgizmo_id: Option<u64>,
 }
 
 impl Gizmo {
@@ -48,9 +95,176 @@ impl Gizmo {
         &self.config
     }
 
+    /// Transform of the gizmo itself, as calculated in the latest [`Gizmo::update`] call.
+    ///
+    /// When there are multiple targets, the translation, scale and rotation
+    /// are averaged from the targets, independent of their order.
+    /// During an interaction, the transform follows the interaction instead.
+    ///
+    /// Useful for displaying or debugging where the gizmo is placed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::{DQuat, DVec3, Pos2, Transform}, Gizmo, GizmoConfig, GizmoInteraction, Rect};
+    /// # let mut gizmo = Gizmo::new(GizmoConfig {
+    /// #     viewport: Rect::from_min_max(Pos2::ZERO, Pos2::new(800.0, 600.0)),
+    /// #     ..Default::default()
+    /// # });
+    /// let target = |angle: f64| {
+    ///     Transform::from_scale_rotation_translation(
+    ///         DVec3::ONE,
+    ///         DQuat::from_rotation_y(angle),
+    ///         DVec3::ZERO,
+    ///     )
+    /// };
+    ///
+    /// gizmo.update(GizmoInteraction::default(), &[target(0.2), target(0.6)]);
+    /// let rotation = DQuat::from(gizmo.transform().rotation);
+    /// assert!(rotation.angle_between(DQuat::from_rotation_y(0.4)) < 1e-6);
+    ///
+    /// gizmo.update(GizmoInteraction::default(), &[target(0.6), target(0.2)]);
+    /// assert!(DQuat::from(gizmo.transform().rotation).angle_between(rotation) < 1e-6);
+    /// ```
+    pub fn transform(&self) -> Transform {
+        self.config.as_transform()
+    }
+
+    /// Combined view-projection matrix used by the gizmo.
+    ///
+    /// Useful for drawing custom overlays that line up exactly with the gizmo.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::{world_to_screen, DVec3}, Gizmo};
+    /// # let gizmo = Gizmo::default();
+    /// let label_pos = world_to_screen(
+    ///     gizmo.config().viewport,
+    ///     gizmo.view_projection(),
+    ///     DVec3::new(1.0, 0.0, 0.0),
+    /// );
+    /// ```
+    pub fn view_projection(&self) -> DMat4 {
+        self.config.view_projection
+    }
+
+    /// Combined model-view-projection matrix used by the gizmo, as calculated
+    /// in the latest [`Gizmo::update`] call. The model matrix is the [`Gizmo::transform`],
+    /// including the scale of the targets.
+    pub fn mvp(&self) -> DMat4 {
+        self.config.mvp
+    }
+
+    /// Screen space bounding rectangle of the gizmo, as placed in the latest
+    /// [`Gizmo::update`] call. Covers the outer rotation circle and the axis handles.
+    ///
+    /// Useful for keeping other UI elements from overlapping the gizmo. Arrows that are
+    /// extended by [`GizmoVisuals::min_arrow_screen_length`](crate::GizmoVisuals::min_arrow_screen_length)
+    /// may reach outside the rectangle.
+    ///
+    /// Returns [`None`] if the gizmo center is behind the camera.
+    pub fn screen_bounds(&self) -> Option<Rect> {
+        let config = &self.config;
+        if !config.is_valid() {
+            return None;
+        }
+
+        let to_screen =
+            |point: DVec3| world_to_screen(config.viewport, config.view_projection, point);
+
+        let center = to_screen(config.translation)?;
+        let radius = outer_circle_radius(config);
+
+        let extents = [
+            config.view_right(),
+            config.view_up(),
+            gizmo_normal(config, GizmoDirection::X),
+            gizmo_normal(config, GizmoDirection::Y),
+            gizmo_normal(config, GizmoDirection::Z),
+        ];
+
+        let bounds = extents
+            .into_iter()
+            .flat_map(|extent| [extent, -extent])
+            .filter_map(|extent| to_screen(config.translation + extent * radius))
+            .fold(Rect::from_min_max(center, center), |bounds, point| {
+                bounds.union(Rect::from_min_max(point, point))
+            });
+
+        Some(bounds)
+    }
+
+    /// Whether a subgizmo started being dragged in the latest [`Gizmo::update`] call.
+    ///
+    /// Useful for opening an undo transaction when an interaction begins.
+    pub fn drag_started(&self) -> bool {
+        self.drag_started
+    }
+
+    /// Whether a subgizmo stopped being dragged in the latest [`Gizmo::update`] call.
+    /// This is the case whenever [`GizmoInteraction::dragging`] turns false during a drag,
+    /// including when the pointer is released outside the viewport.
+    ///
+    /// Useful for closing an undo transaction when an interaction ends.
+    /// Calling [`Gizmo::reset_interaction`] ends the drag without reporting it here.
+    pub fn drag_finished(&self) -> bool {
+        self.drag_finished
+    }
+
+    /// Whether a drag was cancelled with [`GizmoInteraction::cancel`] in the latest
+    /// [`Gizmo::update`] call. A cancelled drag is also reported by [`Gizmo::drag_finished`].
+    ///
+    /// Useful for discarding an undo transaction instead of committing it.
+    pub fn drag_cancelled(&self) -> bool {
+        self.drag_cancelled
+    }
+
+    /// Whether the gizmo can be interacted with and drawn with its current configuration.
+    ///
+    /// The gizmo is inert when the viewport is not set or when the view and projection
+    /// matrices cannot be inverted. An inert gizmo does not react to interaction
+    /// and draws nothing, until it is given a valid configuration.
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::{DMat4, Pos2, Transform}, Gizmo, GizmoConfig, GizmoInteraction, Rect};
+    /// let mut gizmo = Gizmo::new(GizmoConfig {
+    ///     viewport: Rect::from_min_max(Pos2::ZERO, Pos2::new(800.0, 600.0)),
+    ///     // A singular projection matrix
+    ///     projection_matrix: DMat4::ZERO.into(),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert!(!gizmo.is_valid());
+    ///
+    /// let interaction = GizmoInteraction::default();
+    /// assert!(gizmo.update(interaction, &[Transform::default()]).is_none());
+    /// assert!(!gizmo.transform().translation.x.is_nan());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        self.config.is_valid()
+    }
+
     /// Updates the configuration used by the gizmo.
     pub fn update_config(&mut self, config: GizmoConfig) {
-        if config.modes != self.config.modes {
+        // Compare against the configuration as it was given, since the
+        // prepared configuration has out of range values sanitized.
+        if config != self.raw_config {
+            self.invalidate_draw_cache();
+            self.raw_config = config;
+        }
+
+        // A gizmo that can no longer be interacted with drops any ongoing drag.
+        if !config.interactive && self.config.interactive {
+            self.reset_interaction();
+        }
+
+        if config.modes != self.config.modes
+            || config.enabled_directions != self.config.enabled_directions
+            || config.rotation_directions != self.config.rotation_directions
+            || config.planar_2d != self.config.planar_2d
+            || config.dolly_handle != self.config.dolly_handle
+        {
             self.subgizmos.clear();
             self.active_subgizmo_id = None;
         }
@@ -58,27 +272,301 @@ impl Gizmo {
         self.config.update_for_config(config);
 
         if self.subgizmos.is_empty() {
-            for mode in self.config.modes {
-                match mode {
-                    GizmoMode::Rotate => {
-                        self.add_rotation();
-                    }
-                    GizmoMode::Translate => {
-                        self.add_translation();
-                    }
-                    GizmoMode::Scale => {
-                        self.add_scale();
-                    }
-                };
+            if self.config.planar_2d {
+                self.add_planar_2d();
+            } else {
+                for mode in self.config.modes {
+                    match mode {
+                        GizmoMode::Rotate => {
+                            self.add_rotation();
+                        }
+                        GizmoMode::Translate => {
+                            self.add_translation();
+                        }
+                        GizmoMode::Scale => {
+                            self.add_scale();
+                        }
+                    };
+                }
             }
+
+            let enabled_directions = self.config.enabled_directions;
+            let rotation_directions = rotation_directions(self.config.rotation_directions);
+            self.subgizmos.retain(|subgizmo| {
+                is_subgizmo_enabled(
+                    enabled_directions,
+                    subgizmo.direction(),
+                    subgizmo.transform_kind(),
+                ) && (subgizmo.mode() != GizmoMode::Rotate
+                    || rotation_directions.contains(subgizmo.direction()))
+            });
         }
     }
 
+    /// Sets a custom rounding function used for snapping in the given mode.
+    /// It is used instead of rounding to the nearest multiple of
+    /// [`GizmoConfig::snap_angle`], [`GizmoConfig::snap_distance`] or [`GizmoConfig::snap_scale`].
+    ///
+    /// The function is given the unsnapped value and it should return the snapped value.
+    /// The value is the rotation angle in radians for [`GizmoMode::Rotate`],
+    /// the translation distance for [`GizmoMode::Translate`]
+    /// and the scale factor for [`GizmoMode::Scale`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::{Gizmo, GizmoMode};
+    /// let mut gizmo = Gizmo::default();
+    ///
+    /// // Snap scale to powers of two
+    /// gizmo.set_snap_fn(GizmoMode::Scale, |scale| scale.log2().round().exp2());
+    /// ```
+    pub fn set_snap_fn(
+        &mut self,
+        mode: GizmoMode,
+        snap_fn: impl Fn(f64) -> f64 + Send + Sync + 'static,
+    ) {
+        let snap_fn: SnapFn = Arc::new(snap_fn);
+        self.snap_fns.set(mode, Some(snap_fn));
+    }
+
+    /// Removes the custom snapping function of the given mode,
+    /// restoring the default snapping behavior.
+    pub fn clear_snap_fn(&mut self, mode: GizmoMode) {
+        self.snap_fns.set(mode, None);
+    }
+
+    /// Sets scales that scaling snaps to, for matching the size of other objects.
+    ///
+    /// While scaling, the scale of the gizmo snaps to the closest target that is within
+    /// `tolerance` of it. For axis scaling, the scale along the axis is compared
+    /// with the targets. For uniform scaling, the largest scale component is used.
+    /// Planar scaling does not snap to the targets.
+    /// This works independently of [`GizmoConfig::snapping`].
+    ///
+    /// Give an empty list of targets to disable snapping to targets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::Gizmo;
+    /// let mut gizmo = Gizmo::default();
+    ///
+    /// // Snap to the sizes of other objects in the scene
+    /// gizmo.set_scale_snap_targets(vec![0.5, 2.0, 4.0], 0.1);
+    /// ```
+    pub fn set_scale_snap_targets(&mut self, targets: Vec<f64>, tolerance: f64) {
+        self.snap_fns.set_scale_targets(targets, tolerance);
+    }
+
+    /// Sets weights for the targets, used for placing the gizmo at
+    /// a weighted mean of the target translations. For example, weighting the targets
+    /// by their volume places the gizmo at the center of mass of the selection.
+    ///
+    /// The weights are given in the same order as the targets in [`Gizmo::update`].
+    /// If the number of weights does not match the number of targets,
+    /// or the weights sum up to zero, all targets are weighted equally.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::Transform, Gizmo, GizmoInteraction};
+    /// # let mut gizmo = Gizmo::default();
+    /// # let interaction = GizmoInteraction::default();
+    /// # let (small_target, large_target) = (Transform::default(), Transform::default());
+    /// // The large target has three times the volume of the small one
+    /// gizmo.set_target_weights(vec![1.0, 3.0]);
+    /// gizmo.update(interaction, &[small_target, large_target]);
+    /// ```
+    pub fn set_target_weights(&mut self, weights: Vec<f64>) {
+        self.target_weights = weights;
+    }
+
     /// Was this gizmo focused after the latest [`Gizmo::update`] call.
     pub fn is_focused(&self) -> bool {
         self.subgizmos.iter().any(|subgizmo| subgizmo.is_focused())
     }
 
+    /// Mode and direction of the subgizmo that is being dragged, or [`None`] when no drag
+    /// is in progress. The arcball is reported with [`GizmoDirection::View`], and
+    /// pinch gestures like the uniform scale handle.
+    ///
+    /// Useful for showing contextual help about the ongoing interaction.
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::Transform, Gizmo, GizmoInteraction};
+    /// let mut gizmo = Gizmo::default();
+    /// gizmo.update(GizmoInteraction::default(), &[Transform::default()]);
+    ///
+    /// assert!(gizmo.active().is_none());
+    /// ```
+    pub fn active(&self) -> Option<(GizmoMode, GizmoDirection)> {
+        if self.pinch_scale.is_some() {
+            return Some((GizmoMode::Scale, GizmoDirection::View));
+        }
+
+        let id = self.active_subgizmo_id?;
+
+        self.subgizmos
+            .iter()
+            .find(|subgizmo| subgizmo.id() == id)
+            .map(|subgizmo| (subgizmo.mode(), subgizmo.direction()))
+    }
+
+    /// Aborts any ongoing interaction and clears the interaction state of the gizmo.
+    ///
+    /// Useful when the targets change in the middle of a drag, for example due to an undo.
+    /// The next [`Gizmo::update`] call starts fresh, and the dragging needs to be started
+    /// again for the gizmo to be interacted with.
+    pub fn reset_interaction(&mut self) {
+        self.invalidate_draw_cache();
+        self.active_subgizmo_id = None;
+        self.drag_started = false;
+        self.drag_finished = false;
+        self.drag_cancelled = false;
+        self.last_result = None;
+        self.pinch_scale = None;
+        self.pinch_cancelled = false;
+        self.config.axis_lock = None;
+        self.target_start_transforms.clear();
+        self.gizmo_start_transform = Transform::default();
+
+        for subgizmo in &mut self.subgizmos {
+            subgizmo.reset();
+        }
+    }
+
+    /// Tests whether the given screen position is over a subgizmo of the given
+    /// mode and direction, without otherwise interacting with the gizmo.
+    ///
+    /// Useful for building custom overlays, such as tooltips for specific axes.
+    /// The subgizmos are tested against the configuration and targets given in the
+    /// latest [`Gizmo::update`] call. If several subgizmos match, such as the axis
+    /// and the plane of a translation direction, the closest hit is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::prelude::*;
+    /// # use transform_gizmo::math::{DVec3, Pos2, Transform};
+    /// // Camera looking at the origin from the positive Z axis
+    /// let mut gizmo = Gizmo::new(GizmoConfig {
+    ///     modes: enum_set!(GizmoMode::Translate),
+    ///     ..GizmoConfig::from_camera(
+    ///         DVec3::new(0.0, 0.0, 5.0),
+    ///         DVec3::ZERO,
+    ///         DVec3::Y,
+    ///         45f64.to_radians(),
+    ///         0.1,
+    ///         100.0,
+    ///         Rect::from_min_max(Pos2::ZERO, Pos2::new(800.0, 600.0)),
+    ///     )
+    /// });
+    /// gizmo.update(GizmoInteraction::default(), &[Transform::default()]);
+    ///
+    /// // The X axis arrow points to the right from the center of the viewport
+    /// let cursor_pos = (450.0, 300.0);
+    /// assert!(gizmo.hit_test(cursor_pos, GizmoMode::Translate, GizmoDirection::X).is_some());
+    /// assert!(gizmo.hit_test(cursor_pos, GizmoMode::Translate, GizmoDirection::Y).is_none());
+    /// ```
+    pub fn hit_test(
+        &self,
+        screen_pos: (f32, f32),
+        mode: GizmoMode,
+        direction: GizmoDirection,
+    ) -> Option<GizmoHit> {
+        self.pick_closest(screen_pos, |subgizmo| {
+            subgizmo.mode() == mode && subgizmo.direction() == direction
+        })
+    }
+
+    /// Finds the subgizmo that would be focused with the pointer at the given
+    /// screen position, without otherwise interacting with the gizmo.
+    ///
+    /// Useful for showing tooltips for the hovered handle before the next [`Gizmo::update`].
+    /// Like [`Gizmo::hit_test`], this uses the configuration and targets given
+    /// in the latest update, and leaves any ongoing interaction untouched.
+    pub fn pick_preview(&self, screen_pos: (f32, f32)) -> Option<GizmoHit> {
+        self.pick_closest(screen_pos, |_| true)
+    }
+
+    /// Picks copies of the subgizmos accepted by `filter`,
+    /// returning the closest hit at the given screen position.
+    fn pick_closest(
+        &self,
+        screen_pos: (f32, f32),
+        filter: impl Fn(&SubGizmo) -> bool,
+    ) -> Option<GizmoHit> {
+        if !self.config.is_valid() {
+            return None;
+        }
+
+        let ray = self.pointer_ray(Pos2::from(screen_pos));
+        if !self.config.is_pickable(ray.screen_pos) {
+            return None;
+        }
+
+        self.subgizmos
+            .iter()
+            .filter(|subgizmo| filter(subgizmo))
+            // Picking updates the subgizmo state, so pick a copy
+            // to leave any ongoing interaction untouched.
+            .filter_map(|subgizmo| {
+                subgizmo
+                    .clone()
+                    .pick(ray)
+                    .map(|distance| (distance, subgizmo))
+            })
+            .min_by(|(first, _), (second, _)| compare_pick_distance(*first, *second))
+            .map(|(distance, subgizmo)| GizmoHit {
+                mode: subgizmo.mode(),
+                direction: subgizmo.direction(),
+                transform_kind: subgizmo.transform_kind(),
+                distance,
+            })
+    }
+
+    /// Computes the result of dragging a subgizmo from one screen position to another,
+    /// without otherwise interacting with the gizmo.
+    ///
+    /// The subgizmo of the given mode and direction is picked at `from`, like in
+    /// [`Gizmo::hit_test`], and dragged to `to` in a single step. Precision mode and axis
+    /// locks are not applied. Returns [`None`] if the subgizmo is not under `from`.
+    ///
+    /// Useful for automated tests and for previewing where a drag would place the targets.
+    pub fn simulate_drag(
+        &self,
+        mode: GizmoMode,
+        direction: GizmoDirection,
+        from: (f32, f32),
+        to: (f32, f32),
+    ) -> Option<GizmoResult> {
+        if !self.config.is_valid() {
+            return None;
+        }
+
+        let mut config = self.config;
+        config.precision = false;
+        config.snap_toggle = false;
+        config.axis_lock = None;
+
+        let from_ray = self.pointer_ray(Pos2::from(from));
+
+        let (_, mut subgizmo) = self
+            .subgizmos
+            .iter()
+            .filter(|subgizmo| subgizmo.mode() == mode && subgizmo.direction() == direction)
+            .filter_map(|subgizmo| {
+                let mut subgizmo = subgizmo.clone();
+                subgizmo.update_config(config);
+                subgizmo.pick(from_ray).map(|distance| (distance, subgizmo))
+            })
+            .min_by(|(first, _), (second, _)| compare_pick_distance(*first, *second))?;
+
+        subgizmo.set_active(true);
+        subgizmo.update(self.pointer_ray(Pos2::from(to)), &self.snap_fns)
+    }
+
     /// Updates the gizmo based on given interaction information.
     ///
     /// # Examples
@@ -90,12 +578,22 @@ impl Gizmo {
     /// # let cursor_pos = Default::default();
     /// # let drag_started = true;
     /// # let dragging = true;
+    /// # let precision = false;
+    /// # let snap_toggle = false;
+    /// # let cancel = false;
+    /// # let axis_lock_key = None;
+    /// # let pinch_zoom = None;
     /// # let mut transforms = vec![];
     ///
     /// let interaction = GizmoInteraction {
     ///     cursor_pos,
     ///     drag_started,
-    ///     dragging
+    ///     dragging,
+    ///     precision,
+    ///     snap_toggle,
+    ///     cancel,
+    ///     axis_lock_key,
+    ///     pinch_zoom,
     /// };
     ///
     /// if let Some((_result, new_transforms)) = gizmo.update(interaction, &transforms) {
@@ -116,14 +614,52 @@ impl Gizmo {
         interaction: GizmoInteraction,
         targets: &[Transform],
     ) -> Option<(GizmoResult, Vec<Transform>)> {
-        if !self.config.viewport.is_finite() {
+        let draw_state = self.draw_state();
+
+        let result = self.update_interaction(interaction, targets);
+
+        if result.is_some() || self.draw_state() != draw_state {
+            self.invalidate_draw_cache();
+        }
+
+        result
+    }
+
+    fn update_interaction(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[Transform],
+    ) -> Option<(GizmoResult, Vec<Transform>)> {
+        self.drag_started = false;
+        self.drag_finished = false;
+        self.drag_cancelled = false;
+
+        if !self.config.is_valid() {
             return None;
         }
 
+        let was_dragging = self.is_interacting();
+
         // Update the gizmo based on the given target transforms,
         // unless the gizmo is currently being interacted with.
+        if !was_dragging {
+            self.config
+                .update_for_targets(targets, &self.target_weights);
+        }
+
+        self.config.precision = interaction.precision;
+        self.config.snap_toggle = interaction.snap_toggle;
+        self.config.update_idle_amount(
+            self.config
+                .cursor_to_screen(Pos2::from(interaction.cursor_pos)),
+            self.active_subgizmo_id.is_some(),
+        );
+
+        // Axis locks only last for a single drag.
         if self.active_subgizmo_id.is_none() {
-            self.config.update_for_targets(targets);
+            self.config.axis_lock = None;
+        } else if let Some(direction) = interaction.axis_lock_key {
+            self.config.toggle_axis_lock(direction);
         }
 
         for subgizmo in &mut self.subgizmos {
@@ -135,9 +671,19 @@ impl Gizmo {
 
         let pointer_ray = self.pointer_ray(Pos2::from(interaction.cursor_pos));
 
+        // Pinching over the gizmo scales uniformly, unless a subgizmo is being dragged.
+        let pinch_result = if self.active_subgizmo_id.is_none() {
+            self.update_pinch(interaction, targets, pointer_ray.screen_pos)
+        } else {
+            None
+        };
+
         // If there is no active subgizmo, find which one of them
         // is under the mouse pointer, if any.
-        if self.active_subgizmo_id.is_none() {
+        if self.active_subgizmo_id.is_none()
+            && self.pinch_scale.is_none()
+            && self.config.is_pickable(pointer_ray.screen_pos)
+        {
             if let Some(subgizmo) = self.pick_subgizmo(pointer_ray) {
                 subgizmo.set_focused(true);
 
@@ -146,28 +692,54 @@ impl Gizmo {
                     self.active_subgizmo_id = Some(subgizmo.id());
                     self.target_start_transforms = targets.to_vec();
                     self.gizmo_start_transform = self.config.as_transform();
+                    self.last_result = None;
                 }
             }
         }
 
-        let mut result = None;
+        let mut result = pinch_result;
+
+        let active_subgizmo = self.active_subgizmo_id.and_then(|id| {
+            self.subgizmos
+                .iter_mut()
+                .find(|subgizmo| subgizmo.id() == id)
+        });
 
-        if let Some(subgizmo) = self.active_subgizmo_mut() {
-            if interaction.dragging {
+        if let Some(subgizmo) = active_subgizmo {
+            if interaction.dragging && !interaction.cancel {
                 subgizmo.set_active(true);
                 subgizmo.set_focused(true);
-                result = subgizmo.update(pointer_ray);
+                result = subgizmo.update(pointer_ray, &self.snap_fns);
             } else {
+                self.drag_cancelled = interaction.cancel;
                 subgizmo.set_active(false);
                 subgizmo.set_focused(false);
                 self.active_subgizmo_id = None;
             }
         }
 
+        let is_dragging = self.is_interacting();
+        self.drag_started = !was_dragging && is_dragging;
+        self.drag_finished = was_dragging && !is_dragging;
+
+        if self.drag_cancelled {
+            // Return the gizmo and the targets to where they were when the drag started.
+            self.config.update_transform(self.gizmo_start_transform);
+            for subgizmo in &mut self.subgizmos {
+                subgizmo.update_config(self.config);
+            }
+
+            return self
+                .last_result
+                .take()
+                .map(|result| (result.reverted(), self.target_start_transforms.clone()));
+        }
+
         let Some(result) = result else {
             // No interaction, no result.
 
-            self.config.update_for_targets(targets);
+            self.config
+                .update_for_targets(targets, &self.target_weights);
 
             for subgizmo in &mut self.subgizmos {
                 subgizmo.update_config(self.config);
@@ -176,6 +748,7 @@ impl Gizmo {
             return None;
         };
 
+        self.last_result = Some(result);
         self.update_config_with_result(result);
 
         let updated_targets =
@@ -184,30 +757,118 @@ impl Gizmo {
         Some((result, updated_targets))
     }
 
+    /// Updates the gizmo for a single target.
+    ///
+    /// Same as [`Gizmo::update`], but the target can be given as any type that
+    /// converts into a [`Transform`], such as a [`Mat4`](crate::math::Mat4)
+    /// or a [`DMat4`](crate::math::DMat4).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::{DMat4, Mat4}, Gizmo, GizmoInteraction};
+    /// # let mut gizmo = Gizmo::default();
+    /// # let interaction = GizmoInteraction::default();
+    /// let mut model_matrix = Mat4::IDENTITY;
+    ///
+    /// if let Some((_result, new_transform)) = gizmo.update_single(interaction, model_matrix) {
+    ///     model_matrix = DMat4::from(new_transform).as_mat4();
+    /// }
+    /// ```
+    pub fn update_single(
+        &mut self,
+        interaction: GizmoInteraction,
+        target: impl Into<Transform>,
+    ) -> Option<(GizmoResult, Transform)> {
+        self.update(interaction, &[target.into()])
+            .and_then(|(result, transforms)| Some((result, *transforms.first()?)))
+    }
+
     /// Return all the necessary data to draw the latest gizmo interaction.
     ///
     /// The gizmo draw data consists of vertices in viewport coordinates.
-    pub fn draw(&self) -> GizmoDrawData {
-        if !self.config.viewport.is_finite() {
+    ///
+    /// The draw data is cached, so drawing a gizmo whose configuration, targets and
+    /// interaction state have not changed since the previous call is cheap.
+    #[cfg(feature = "draw")]
+    pub fn draw(&self) -> &GizmoDrawData {
+        self.draw_cache.get_or_init(|| self.generate_draw_data())
+    }
+
+    #[cfg(feature = "draw")]
+    fn generate_draw_data(&self) -> GizmoDrawData {
+        if !self.config.is_valid() || self.config.opacity <= 0.0 {
             return GizmoDrawData::default();
         }
 
-        let mut draw_data = GizmoDrawData::default();
-        for subgizmo in &self.subgizmos {
-            if self.active_subgizmo_id.is_none() || subgizmo.is_active() {
-                draw_data += subgizmo.draw();
+        let hide_inactive =
+            self.config.visuals.hide_inactive_during_drag && self.active_subgizmo_id.is_some();
+
+        let mut parts = self
+            .subgizmos
+            .iter()
+            .filter(|subgizmo| !hide_inactive || subgizmo.is_active())
+            .map(|subgizmo| subgizmo.draw())
+            .collect::<Vec<_>>();
+
+        if self.config.visuals.gimbal_rings && self.config.modes.contains(GizmoMode::Rotate) {
+            parts.push(draw_gimbal_rings(&self.config));
+        }
+
+        if let Some(ground_height) = self.config.visuals.ground_height {
+            parts.push(draw_ground_shadow(&self.config, ground_height as f64));
+        }
+
+        let mut draw_data = GizmoDrawData::merge(parts);
+
+        if self.config.opacity < 1.0 {
+            // The colors are premultiplied, so every channel is faded.
+            for color in &mut draw_data.colors {
+                *color = color.map(|channel| channel * self.config.opacity);
             }
         }
 
         draw_data
     }
 
-    fn active_subgizmo_mut(&mut self) -> Option<&mut SubGizmo> {
-        self.active_subgizmo_id.and_then(|id| {
-            self.subgizmos
-                .iter_mut()
-                .find(|subgizmo| subgizmo.id() == id)
-        })
+    /// Return the data to draw the gizmo at a pose interpolated from the given
+    /// transform towards the current transform of the gizmo.
+    ///
+    /// Useful for animating the gizmo when the targets are moved programmatically,
+    /// instead of having it jump to the new transform. This only affects drawing.
+    /// The gizmo is interacted with at its actual transform.
+    ///
+    /// `t` goes from 0.0, where the gizmo is drawn at `from`,
+    /// to 1.0, where it is drawn at [`Gizmo::transform`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::Transform, Gizmo};
+    /// # let gizmo = Gizmo::default();
+    /// # let previous_transform = Transform::default();
+    /// # let elapsed = 0.1;
+    /// let duration = 0.2;
+    /// let t = (elapsed / duration).min(1.0);
+    ///
+    /// let draw_data = gizmo.draw_interpolated(previous_transform, t);
+    /// ```
+    #[cfg(feature = "draw")]
+    pub fn draw_interpolated(&self, from: Transform, t: f64) -> GizmoDrawData {
+        if t >= 1.0 {
+            return self.draw().clone();
+        }
+
+        let mut tweened = self.clone();
+        tweened
+            .config
+            .update_transform(from.lerp(&self.config.as_transform(), t.max(0.0)));
+
+        for subgizmo in &mut tweened.subgizmos {
+            subgizmo.update_config(tweened.config);
+        }
+
+        tweened.generate_draw_data()
     }
 
     fn update_transforms_with_result(
@@ -223,14 +884,14 @@ impl Gizmo {
                 GizmoResult::Rotation {
                     axis,
                     delta,
-                    total: _,
                     is_view_axis,
+                    ..
                 } => self.update_rotation(transform, axis, delta, is_view_axis),
-                GizmoResult::Translation { delta, total: _ } => {
+                GizmoResult::Translation { delta, .. } => {
                     self.update_translation(delta, transform, start_transform)
                 }
-                GizmoResult::Scale { total } => {
-                    Self::update_scale(transform, start_transform, total)
+                GizmoResult::Scale { total, .. } => {
+                    self.update_scale(transform, start_transform, total)
                 }
                 GizmoResult::Arcball { delta, total: _ } => {
                     self.update_rotation_quat(transform, delta.into())
@@ -260,9 +921,11 @@ impl Gizmo {
 
     fn update_rotation_quat(&self, transform: &Transform, delta: DQuat) -> Transform {
         let translation = match self.config.pivot_point {
-            TransformPivotPoint::MedianPoint => (self.config.translation
-                + delta * (DVec3::from(transform.translation) - self.config.translation))
-                .into(),
+            TransformPivotPoint::MedianPoint | TransformPivotPoint::ActiveElement => {
+                (self.config.translation
+                    + delta * (DVec3::from(transform.translation) - self.config.translation))
+                    .into()
+            }
             TransformPivotPoint::IndividualOrigins => transform.translation,
         };
 
@@ -292,14 +955,29 @@ impl Gizmo {
     }
 
     fn update_scale(
+        &self,
         transform: &Transform,
         start_transform: &Transform,
         scale: mint::Vector3<f64>,
     ) -> Transform {
+        let translation = match self.config.pivot_point {
+            TransformPivotPoint::MedianPoint | TransformPivotPoint::ActiveElement => {
+                // Positions are scaled away from the gizmo, along the axes of the gizmo.
+                let pivot = DVec3::from(self.gizmo_start_transform.translation);
+                let rotation = DQuat::from(self.gizmo_start_transform.rotation);
+                let offset = rotation.inverse()
+                    * (DVec3::from(start_transform.translation) - pivot)
+                    * DVec3::from(scale);
+
+                (pivot + rotation * offset).into()
+            }
+            TransformPivotPoint::IndividualOrigins => transform.translation,
+        };
+
         Transform {
             scale: (DVec3::from(start_transform.scale) * DVec3::from(scale)).into(),
             rotation: transform.rotation,
-            translation: transform.translation,
+            translation,
         }
     }
 
@@ -318,11 +996,7 @@ impl Gizmo {
         self.subgizmos
             .iter_mut()
             .filter_map(|subgizmo| subgizmo.pick(ray).map(|t| (t, subgizmo)))
-            .min_by(|(first, _), (second, _)| {
-                first
-                    .partial_cmp(second)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            })
+            .min_by(|(first, _), (second, _)| compare_pick_distance(*first, *second))
             .map(|(_, subgizmo)| subgizmo)
     }
 
@@ -400,6 +1074,19 @@ impl Gizmo {
             .into(),
         ]);
 
+        if self.config.dolly_handle {
+            self.subgizmos.push(
+                TranslationSubGizmo::new(
+                    self.config,
+                    TranslationParams {
+                        direction: GizmoDirection::View,
+                        transform_kind: TransformKind::Axis,
+                    },
+                )
+                .into(),
+            );
+        }
+
         // Plane subgizmos are not added when both translation and scaling are enabled.
         if !self.config.modes.contains(GizmoMode::Scale) {
             self.subgizmos.extend([
@@ -506,19 +1193,207 @@ impl Gizmo {
         }
     }
 
-    /// Calculate a world space ray from given screen space position
-    fn pointer_ray(&self, screen_pos: Pos2) -> Ray {
-        let mat = self.config.view_projection.inverse();
-        let origin = screen_to_world(self.config.viewport, mat, screen_pos, -1.0);
-        let target = screen_to_world(self.config.viewport, mat, screen_pos, 1.0);
+    /// Adds the subgizmos of the enabled modes that transform in the XY plane only
+    fn add_planar_2d(&mut self) {
+        let modes = self.config.modes;
+
+        if modes.contains(GizmoMode::Rotate) {
+            self.subgizmos.push(
+                RotationSubGizmo::new(
+                    self.config,
+                    RotationParams {
+                        direction: GizmoDirection::Z,
+                    },
+                )
+                .into(),
+            );
+        }
+
+        if modes.contains(GizmoMode::Translate) {
+            for direction in [GizmoDirection::X, GizmoDirection::Y] {
+                self.subgizmos.push(
+                    TranslationSubGizmo::new(
+                        self.config,
+                        TranslationParams {
+                            direction,
+                            transform_kind: TransformKind::Axis,
+                        },
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        // Scaling the XY plane scales X and Y uniformly, leaving Z untouched.
+        if modes.contains(GizmoMode::Scale) {
+            self.subgizmos.push(
+                ScaleSubGizmo::new(
+                    self.config,
+                    ScaleParams {
+                        direction: GizmoDirection::Z,
+                        transform_kind: TransformKind::Plane,
+                    },
+                )
+                .into(),
+            );
+        }
+    }
+
+    /// Snapshot of the state that the draw data depends on.
+    fn draw_state(&self) -> DrawState {
+        DrawState {
+            transform: self.config.as_transform(),
+            idle_amount: self.config.idle_amount,
+            focused_subgizmo_id: self
+                .subgizmos
+                .iter()
+                .find(|subgizmo| subgizmo.is_focused())
+                .map(|subgizmo| subgizmo.id()),
+            active_subgizmo_id: self.active_subgizmo_id,
+        }
+    }
 
-        let direction = target.sub(origin).normalize();
+    /// Discards the cached draw data, so that it is regenerated when drawn next.
+    #[cfg_attr(not(feature = "draw"), allow(clippy::unused_self))]
+    fn invalidate_draw_cache(&mut self) {
+        #[cfg(feature = "draw")]
+        {
+            self.draw_cache = OnceLock::new();
+        }
+    }
 
-        Ray {
-            screen_pos,
-            origin,
-            direction,
+    /// Whether a subgizmo is being dragged or a pinch gesture is in progress.
+    fn is_interacting(&self) -> bool {
+        self.active_subgizmo_id.is_some() || self.pinch_scale.is_some()
+    }
+
+    /// Updates the ongoing pinch gesture, starting one if a pinch begins over the gizmo.
+    /// Returns the resulting uniform scale, if the gesture is in progress.
+    fn update_pinch(
+        &mut self,
+        interaction: GizmoInteraction,
+        targets: &[Transform],
+        screen_pos: Pos2,
+    ) -> Option<GizmoResult> {
+        let Some(zoom) = interaction.pinch_zoom else {
+            self.pinch_scale = None;
+            self.pinch_cancelled = false;
+            return None;
+        };
+
+        if interaction.cancel || self.pinch_cancelled {
+            self.drag_cancelled = self.pinch_scale.is_some();
+            self.pinch_cancelled = true;
+            self.pinch_scale = None;
+            return None;
         }
+
+        let start_scale = match self.pinch_scale {
+            Some(scale) => scale,
+            None if self.config.modes.contains(GizmoMode::Scale)
+                && self.config.is_pickable(screen_pos)
+                && self
+                    .screen_bounds()
+                    .is_some_and(|bounds| bounds.contains(screen_pos)) =>
+            {
+                self.target_start_transforms = targets.to_vec();
+                self.gizmo_start_transform = self.config.as_transform();
+                self.last_result = None;
+                1.0
+            }
+            None => return None,
+        };
+
+        let raw_scale = start_scale * (zoom as f64).powf(self.config.motion_scale());
+        self.pinch_scale = Some(raw_scale);
+
+        let mut scale = raw_scale;
+        if self.config.is_snapping() {
+            scale = self
+                .snap_fns
+                .snap(GizmoMode::Scale, scale, self.config.snap_scale as f64);
+        }
+
+        // Planar gizmos leave the Z axis unscaled.
+        let axes = if self.config.planar_2d {
+            DVec3::new(1.0, 1.0, 0.0)
+        } else {
+            DVec3::ONE
+        };
+
+        let mut total = DVec3::ONE + axes * (scale.max(1e-4) - 1.0);
+        if let Some((min, max)) = self.config.scale_clamp {
+            total = clamp_scale(
+                self.gizmo_start_transform.scale.into(),
+                total,
+                axes,
+                min,
+                max,
+            );
+        }
+
+        let last_total = self
+            .last_result
+            .and_then(|result| result.total_scale())
+            .unwrap_or(DVec3::ONE);
+
+        Some(GizmoResult::Scale {
+            delta: (total / last_total).into(),
+            total: total.into(),
+            raw_total: (DVec3::ONE + axes * (raw_scale.max(1e-4) - 1.0)).into(),
+            snapped: scale != raw_scale,
+            direction: GizmoDirection::View,
+            transform_kind: TransformKind::Plane,
+            space: GizmoOrientation::Local,
+        })
+    }
+
+    /// Calculate a world space ray from given cursor position
+    fn pointer_ray(&self, cursor_pos: Pos2) -> Ray {
+        self.config.ray_from_screen(cursor_pos)
+    }
+}
+
+/// Whether a subgizmo is available with the given enabled directions.
+/// Plane subgizmos need both of the axes spanning the plane.
+fn is_subgizmo_enabled(
+    enabled_directions: EnumSet<GizmoDirection>,
+    direction: GizmoDirection,
+    transform_kind: TransformKind,
+) -> bool {
+    match (transform_kind, direction) {
+        (TransformKind::Plane, GizmoDirection::X) => {
+            enabled_directions.is_superset(GizmoDirection::Y | GizmoDirection::Z)
+        }
+        (TransformKind::Plane, GizmoDirection::Y) => {
+            enabled_directions.is_superset(GizmoDirection::X | GizmoDirection::Z)
+        }
+        (TransformKind::Plane, GizmoDirection::Z) => {
+            enabled_directions.is_superset(GizmoDirection::X | GizmoDirection::Y)
+        }
+        (_, direction) => enabled_directions.contains(direction),
+    }
+}
+
+/// Directions of the rotation subgizmos. With a single rotation axis,
+/// the view ring and the arcball would allow rotating around other axes,
+/// so they are left out.
+fn rotation_directions(directions: EnumSet<GizmoDirection>) -> EnumSet<GizmoDirection> {
+    let axes = directions - GizmoDirection::View;
+    if axes.len() == 1 {
+        axes
+    } else {
+        directions
+    }
+}
+
+/// Orders pick distances nearest first. Equal distances keep the order of the
+/// subgizmos, so overlapping hits resolve the same way every frame.
+/// NaN distances are ordered last, so that they never win over a valid hit.
+fn compare_pick_distance(first: f64, second: f64) -> std::cmp::Ordering {
+    match (first.is_nan(), second.is_nan()) {
+        (false, false) => first.total_cmp(&second),
+        (first_nan, second_nan) => first_nan.cmp(&second_nan),
     }
 }
 
@@ -535,6 +1410,44 @@ pub struct GizmoInteraction {
     /// Usually this is set to true whenever the primary mouse
     /// button is being pressed.
     pub dragging: bool,
+    /// Whether precision mode is active, for fine adjustments.
+    /// Usually this is set to true while a modifier key such as Alt is held.
+    /// In precision mode, the gizmo moves [`GizmoConfig::precision_factor`]
+    /// times as much as the pointer.
+    pub precision: bool,
+    /// Whether snapping is temporarily toggled, inverting [`GizmoConfig::snapping`].
+    /// Usually this is set to true while a modifier key such as Ctrl is held.
+    /// [`GizmoConfig::snapping`] itself is left unchanged.
+    pub snap_toggle: bool,
+    /// Whether to cancel the ongoing drag, returning the targets to their transforms from
+    /// when the drag started. Usually this is set to true when the Escape key was just pressed.
+    pub cancel: bool,
+    /// Axis key that was pressed this frame, if any.
+    /// Usually this is set when the X, Y or Z key was just pressed.
+    ///
+    /// During a free translation drag, pressing an axis key locks the motion to that axis.
+    /// Pressing it again locks to the plane perpendicular to the axis, and a third press
+    /// unlocks. [`GizmoDirection::View`] removes the lock. The lock is released when the drag ends.
+    pub axis_lock_key: Option<GizmoDirection>,
+    /// Zoom ratio of a two-finger pinch gesture since the previous update, or [`None`] when
+    /// no pinch is in progress. A pinch over the gizmo scales the targets uniformly if
+    /// [`GizmoMode::Scale`] is enabled.
+    pub pinch_zoom: Option<f32>,
+}
+
+/// Result of [`Gizmo::hit_test`] and [`Gizmo::pick_preview`].
+#[derive(Debug, Copy, Clone)]
+pub struct GizmoHit {
+    /// Mode of the subgizmo that was hit
+    pub mode: GizmoMode,
+    /// Direction of the subgizmo that was hit
+    pub direction: GizmoDirection,
+    /// Whether the subgizmo that was hit transforms along an axis or on a plane
+    pub transform_kind: TransformKind,
+    /// Distance from the camera to the subgizmo along the pointer ray.
+    /// Subgizmos that are not picked by distance, such as the arcball,
+    /// report [`f64::MAX`].
+    pub distance: f64,
 }
 
 /// Result of a gizmo transformation
@@ -547,18 +1460,56 @@ pub enum GizmoResult {
         delta: f64,
         /// Total rotation angle of the gizmo interaction
         total: f64,
+        /// Total rotation angle of the gizmo interaction before snapping.
+        /// Equal to `total` when snapping is disabled.
+        raw_total: f64,
+        /// Whether snapping changed the rotation this frame
+        snapped: bool,
         /// Whether we are rotating along the view axis
         is_view_axis: bool,
+        /// Direction of the rotation ring that was dragged
+        direction: GizmoDirection,
+        /// Space that `axis` is given in. The view axis is always in world space.
+        space: GizmoOrientation,
     },
     Translation {
         /// The latest translation delta
         delta: mint::Vector3<f64>,
         /// Total translation of the gizmo interaction
         total: mint::Vector3<f64>,
+        /// Total translation of the gizmo interaction before snapping.
+        /// Equal to `total` when snapping is disabled.
+        raw_total: mint::Vector3<f64>,
+        /// Whether snapping changed the translation this frame
+        snapped: bool,
+        /// Whether the translation was limited by [`GizmoConfig::translation_bounds`] this frame
+        clamped: bool,
+        /// Direction of the subgizmo that was dragged
+        direction: GizmoDirection,
+        /// Whether an axis or a plane was dragged
+        transform_kind: TransformKind,
+        /// Space that `delta`, `total` and `raw_total` are given in
+        space: GizmoOrientation,
     },
     Scale {
+        /// The latest scale delta. Multiplying the scale of a target by this
+        /// each frame gives the same result as applying `total` to the scale
+        /// the target had when the interaction started.
+        delta: mint::Vector3<f64>,
         /// Total scale of the gizmo interaction
         total: mint::Vector3<f64>,
+        /// Total scale of the gizmo interaction before snapping.
+        /// Equal to `total` when snapping is disabled.
+        raw_total: mint::Vector3<f64>,
+        /// Whether snapping changed the scale this frame
+        snapped: bool,
+        /// Direction of the subgizmo that was dragged
+        direction: GizmoDirection,
+        /// Whether an axis or a plane was dragged
+        transform_kind: TransformKind,
+        /// Space that `total` and `raw_total` are given in.
+        /// Scaling is always done in local space.
+        space: GizmoOrientation,
     },
     Arcball {
         /// The latest rotation delta
@@ -568,6 +1519,236 @@ pub enum GizmoResult {
     },
 }
 
+impl GizmoResult {
+    /// Space that the axes and vectors of the result are given in. With [`GizmoOrientation::Local`],
+    /// they are relative to the orientation of the gizmo, and are applied relative to the
+    /// orientation of each target. Arcball rotations are always in world space.
+    pub fn space(&self) -> GizmoOrientation {
+        match *self {
+            Self::Rotation { space, .. }
+            | Self::Translation { space, .. }
+            | Self::Scale { space, .. } => space,
+            Self::Arcball { .. } => GizmoOrientation::Global,
+        }
+    }
+
+    /// Direction of the subgizmo that produced the result.
+    ///
+    /// Returns [`None`] for arcball rotations, which are not bound to a direction.
+    pub fn direction(&self) -> Option<GizmoDirection> {
+        match *self {
+            Self::Rotation { direction, .. }
+            | Self::Translation { direction, .. }
+            | Self::Scale { direction, .. } => Some(direction),
+            Self::Arcball { .. } => None,
+        }
+    }
+
+    /// Whether the result is from dragging an axis or a plane.
+    /// Rotation rings transform around an axis.
+    ///
+    /// Returns [`None`] for arcball rotations, which are not bound to a direction.
+    pub fn transform_kind(&self) -> Option<TransformKind> {
+        match *self {
+            Self::Rotation { .. } => Some(TransformKind::Axis),
+            Self::Translation { transform_kind, .. } | Self::Scale { transform_kind, .. } => {
+                Some(transform_kind)
+            }
+            Self::Arcball { .. } => None,
+        }
+    }
+
+    /// The latest rotation angle delta in the given unit.
+    /// For arcball rotations, this is the angle of the rotation delta, which is never negative.
+    ///
+    /// Returns [`None`] if this is not a rotation result.
+    pub fn delta_angle(&self, unit: AngleUnit) -> Option<f64> {
+        let radians = match *self {
+            Self::Rotation { delta, .. } => delta,
+            Self::Arcball { delta, .. } => DQuat::from(delta).to_axis_angle().1,
+            Self::Translation { .. } | Self::Scale { .. } => return None,
+        };
+
+        Some(unit.convert(radians))
+    }
+
+    /// Total rotation angle of the gizmo interaction in the given unit.
+    /// For arcball rotations, this is the angle of the total rotation, which is never negative.
+    ///
+    /// Returns [`None`] if this is not a rotation result.
+    pub fn total_angle(&self, unit: AngleUnit) -> Option<f64> {
+        let radians = match *self {
+            Self::Rotation { total, .. } => total,
+            Self::Arcball { total, .. } => DQuat::from(total).to_axis_angle().1,
+            Self::Translation { .. } | Self::Scale { .. } => return None,
+        };
+
+        Some(unit.convert(radians))
+    }
+
+    /// Total translation since the drag started, or [`None`] if this is not a translation.
+    /// Like the `total` field, the translation is in local space with local orientation.
+    pub fn total_translation(&self) -> Option<DVec3> {
+        match *self {
+            Self::Translation { total, .. } => Some(total.into()),
+            _ => None,
+        }
+    }
+
+    /// Total rotation since the drag started, or [`None`] if this is not a rotation.
+    /// Rotations around an axis are converted to a quaternion, with the axis
+    /// in local space with local orientation, like the `axis` field.
+    pub fn total_rotation(&self) -> Option<DQuat> {
+        match *self {
+            // The total angle has the opposite sign of the applied rotation.
+            Self::Rotation { axis, total, .. } => {
+                Some(DQuat::from_axis_angle(DVec3::from(axis), -total))
+            }
+            Self::Arcball { total, .. } => Some(total.into()),
+            Self::Translation { .. } | Self::Scale { .. } => None,
+        }
+    }
+
+    /// Scale factor since the previous update, or [`None`] if this is not a scaling.
+    pub fn delta_scale(&self) -> Option<DVec3> {
+        match *self {
+            Self::Scale { delta, .. } => Some(delta.into()),
+            _ => None,
+        }
+    }
+
+    /// Total scale factor since the drag started, or [`None`] if this is not a scaling.
+    pub fn total_scale(&self) -> Option<DVec3> {
+        match *self {
+            Self::Scale { total, .. } => Some(total.into()),
+            _ => None,
+        }
+    }
+
+    /// Result that reverts the interaction that this result is the latest result of.
+    /// The totals are reset, and the deltas undo the totals.
+    fn reverted(self) -> Self {
+        match self {
+            Self::Rotation {
+                axis,
+                total,
+                is_view_axis,
+                direction,
+                space,
+                ..
+            } => Self::Rotation {
+                axis,
+                // The total angle has the opposite sign of the applied rotation.
+                delta: total,
+                total: 0.0,
+                raw_total: 0.0,
+                snapped: false,
+                is_view_axis,
+                direction,
+                space,
+            },
+            Self::Translation {
+                total,
+                direction,
+                transform_kind,
+                space,
+                ..
+            } => Self::Translation {
+                delta: (-DVec3::from(total)).into(),
+                total: DVec3::ZERO.into(),
+                raw_total: DVec3::ZERO.into(),
+                snapped: false,
+                clamped: false,
+                direction,
+                transform_kind,
+                space,
+            },
+            Self::Scale {
+                total,
+                direction,
+                transform_kind,
+                space,
+                ..
+            } => Self::Scale {
+                delta: (DVec3::ONE / DVec3::from(total)).into(),
+                total: DVec3::ONE.into(),
+                raw_total: DVec3::ONE.into(),
+                snapped: false,
+                direction,
+                transform_kind,
+                space,
+            },
+            Self::Arcball { total, .. } => Self::Arcball {
+                delta: DQuat::from(total).inverse().into(),
+                total: DQuat::IDENTITY.into(),
+            },
+        }
+    }
+
+    /// Applies the total transformation of the interaction to a target matrix.
+    ///
+    /// `target` is **set** to `start` composed with the total transformation, where `start`
+    /// is the matrix of the target when the interaction started. The previous value of
+    /// `target` is not used, so calling this every frame with the same `start` matrix
+    /// gives the same result as accumulating the deltas.
+    ///
+    /// The transformation is applied around the origin of the target, with the axes
+    /// oriented like the gizmo of `config`. This matches [`Gizmo::update`] for a single target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::{DMat4, DVec3}, GizmoConfig, GizmoDirection, GizmoOrientation, GizmoResult, TransformKind};
+    /// let start = DMat4::from_translation(DVec3::new(1.0, 0.0, 0.0));
+    /// let mut target = start;
+    ///
+    /// let result = GizmoResult::Translation {
+    ///     delta: DVec3::new(0.0, 1.0, 0.0).into(),
+    ///     total: DVec3::new(0.0, 2.0, 0.0).into(),
+    ///     raw_total: DVec3::new(0.0, 2.0, 0.0).into(),
+    ///     snapped: false,
+    ///     clamped: false,
+    ///     direction: GizmoDirection::Y,
+    ///     transform_kind: TransformKind::Axis,
+    ///     space: GizmoOrientation::Global,
+    /// };
+    /// result.apply_to(&GizmoConfig::default(), &start, &mut target);
+    ///
+    /// assert_eq!(target.w_axis.truncate(), DVec3::new(1.0, 2.0, 0.0));
+    /// ```
+    pub fn apply_to(&self, config: &GizmoConfig, start: &DMat4, target: &mut DMat4) {
+        let (scale, rotation, translation) = start.to_scale_rotation_translation();
+        let local = config.orientation() == GizmoOrientation::Local;
+
+        let (scale, rotation, translation) = match *self {
+            Self::Rotation {
+                axis,
+                total,
+                is_view_axis,
+                ..
+            } => {
+                let mut axis = DVec3::from(axis);
+                if local && !is_view_axis {
+                    axis = rotation * axis;
+                }
+                let total = DQuat::from_axis_angle(axis, -total);
+                (scale, total * rotation, translation)
+            }
+            Self::Translation { total, .. } => {
+                let mut total = DVec3::from(total);
+                if local {
+                    total = rotation * total;
+                }
+                (scale, rotation, translation + total)
+            }
+            Self::Scale { total, .. } => (scale * DVec3::from(total), rotation, translation),
+            Self::Arcball { total, .. } => (scale, DQuat::from(total) * rotation, translation),
+        };
+
+        *target = DMat4::from_scale_rotation_translation(scale, rotation, translation);
+    }
+}
+
 /// Data used to draw [`Gizmo`].
 #[derive(Default, Clone, Debug)]
 pub struct GizmoDrawData {
@@ -579,6 +1760,116 @@ pub struct GizmoDrawData {
     pub indices: Vec<u32>,
 }
 
+impl GizmoDrawData {
+    /// Concatenates draw data into a single buffer, in the given order.
+    /// The indices are offset to point to the vertices of their own draw data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::GizmoDrawData;
+    /// let triangle = GizmoDrawData {
+    ///     vertices: vec![[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]],
+    ///     colors: vec![[1.0, 0.0, 0.0, 1.0]; 3],
+    ///     indices: vec![0, 1, 2],
+    /// };
+    ///
+    /// let merged = GizmoDrawData::merge([triangle.clone(), triangle]);
+    /// assert_eq!(merged.vertices.len(), 6);
+    /// assert_eq!(merged.indices, [0, 1, 2, 3, 4, 5]);
+    /// ```
+    pub fn merge(parts: impl IntoIterator<Item = Self>) -> Self {
+        let parts = parts.into_iter().collect::<Vec<_>>();
+
+        let mut merged = Self {
+            vertices: Vec::with_capacity(parts.iter().map(|part| part.vertices.len()).sum()),
+            colors: Vec::with_capacity(parts.iter().map(|part| part.colors.len()).sum()),
+            indices: Vec::with_capacity(parts.iter().map(|part| part.indices.len()).sum()),
+        };
+        for part in parts {
+            merged += part;
+        }
+
+        merged
+    }
+
+    /// Serializes the draw data into an SVG document covering the given viewport.
+    ///
+    /// Each triangle is written as its own `<polygon>`, filled with the average color of
+    /// its vertices, so color gradients are flattened and thin seams may show between
+    /// adjacent triangles due to anti-aliasing. Triangles with out of range indices are
+    /// skipped. Useful for producing vector images of the gizmo without a GPU,
+    /// for example for documentation or visual regression tests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use transform_gizmo::{math::Pos2, GizmoDrawData, Rect};
+    /// let draw_data = GizmoDrawData {
+    ///     vertices: vec![[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]],
+    ///     colors: vec![[1.0, 0.0, 0.0, 1.0]; 3],
+    ///     indices: vec![0, 1, 2],
+    /// };
+    ///
+    /// let svg = draw_data.to_svg(Rect::from_min_max(Pos2::ZERO, Pos2::new(10.0, 10.0)));
+    /// assert!(svg.starts_with("<svg"));
+    /// assert!(svg.contains(r##"<polygon points="0,0 10,0 0,10" fill="#ff0000"/>"##));
+    /// ```
+    pub fn to_svg(&self, viewport: Rect) -> String {
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+            viewport.width(),
+            viewport.height(),
+            viewport.min.x,
+            viewport.min.y,
+            viewport.width(),
+            viewport.height(),
+        );
+        svg.push('\n');
+
+        let corner = |index: u32| {
+            let index = index as usize;
+            Some((*self.vertices.get(index)?, *self.colors.get(index)?))
+        };
+
+        for triangle in self.indices.chunks_exact(3) {
+            let (Some(first), Some(second), Some(third)) = (
+                corner(triangle[0]),
+                corner(triangle[1]),
+                corner(triangle[2]),
+            ) else {
+                continue;
+            };
+            let corners = [first, second, third];
+
+            let mut color = Rgba::TRANSPARENT;
+            for (_, [r, g, b, a]) in corners {
+                color = color + Rgba::from_rgba_premultiplied(r, g, b, a) * (1.0 / 3.0);
+            }
+
+            let [r, g, b, a] = Color32::from(color).to_srgba_unmultiplied();
+            if a == 0 {
+                continue;
+            }
+
+            svg.push_str(r#"<polygon points=""#);
+            for (i, ([x, y], _)) in corners.into_iter().enumerate() {
+                let separator = if i == 0 { "" } else { " " };
+                write!(svg, "{separator}{x},{y}").unwrap();
+            }
+            write!(svg, r##"" fill="#{r:02x}{g:02x}{b:02x}""##).unwrap();
+            if a < u8::MAX {
+                write!(svg, r#" fill-opacity="{:.3}""#, a as f32 / 255.0).unwrap();
+            }
+            svg.push_str("/>\n");
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(feature = "draw")]
 impl From<Mesh> for GizmoDrawData {
     fn from(mesh: Mesh) -> Self {
         let (vertices, colors): (Vec<_>, Vec<_>) = mesh
@@ -619,9 +1910,14 @@ impl Add for GizmoDrawData {
     }
 }
 
+/// A world space ray cast from a position on the screen,
+/// created with [`GizmoConfig::ray_from_screen`].
 #[derive(Debug, Copy, Clone)]
-pub(crate) struct Ray {
-    pub(crate) screen_pos: Pos2,
-    pub(crate) origin: DVec3,
-    pub(crate) direction: DVec3,
+pub struct Ray {
+    /// Screen position of the ray, with a top-left origin.
+    pub screen_pos: Pos2,
+    /// Origin of the ray on the near plane.
+    pub origin: DVec3,
+    /// Normalized direction of the ray.
+    pub direction: DVec3,
 }
@@ -15,9 +15,15 @@
 //! For rendering the gizmo, [`Gizmo::draw`] provides vertices in viewport coordinates that can be easily rendered
 //! with your favorite graphics APIs.
 //!
+//! # Features
+//!
+//! - `draw` (enabled by default): Enables [`Gizmo::draw`]. Without it, the crate does not
+//!   depend on `epaint` and only provides the interaction logic of the gizmo.
+//!
 //! For a more complete example, see the online demo at <https://urholaukkarinen.github.io/transform-gizmo/>.
 //! The demo sources can be found at <https://github.com/urholaukkarinen/transform-gizmo/blob/main/examples/bevy/src/main.rs>.
 
+#[cfg(feature = "draw")]
 mod shape;
 mod subgizmo;
 
@@ -28,3 +34,6 @@ pub mod math;
 pub mod prelude;
 
 pub use prelude::*;
+
+#[cfg(test)]
+mod tests;
@@ -1,5 +1,7 @@
 pub use emath::{Pos2, Rect, Vec2};
-pub use glam::{DMat3, DMat4, DQuat, DVec2, DVec3, DVec4, Mat4, Quat, Vec3, Vec4Swizzles};
+pub use glam::{
+    DMat3, DMat4, DQuat, DVec2, DVec3, DVec4, EulerRot, Mat4, Quat, Vec3, Vec4Swizzles,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub struct Transform {
@@ -30,12 +32,97 @@ impl Transform {
             translation: translation.into(),
         }
     }
+
+    /// Interpolates between this and another transform.
+    /// Translation and scale are interpolated linearly and rotation spherically.
+    ///
+    /// ```
+    /// # use transform_gizmo::math::{DQuat, DVec3, Transform};
+    /// let from = Transform::default();
+    /// let to = Transform::from_scale_rotation_translation(
+    ///     DVec3::splat(3.0),
+    ///     DQuat::from_rotation_y(1.0),
+    ///     DVec3::new(2.0, 0.0, 0.0),
+    /// );
+    ///
+    /// let halfway = from.lerp(&to, 0.5);
+    /// assert_eq!(DVec3::from(halfway.translation), DVec3::new(1.0, 0.0, 0.0));
+    /// assert_eq!(DVec3::from(halfway.scale), DVec3::splat(2.0));
+    /// assert!(DQuat::from(halfway.rotation).abs_diff_eq(DQuat::from_rotation_y(0.5), 1e-10));
+    /// ```
+    pub fn lerp(&self, other: &Self, t: f64) -> Self {
+        Self {
+            scale: DVec3::from(self.scale).lerp(other.scale.into(), t).into(),
+            rotation: DQuat::from(self.rotation)
+                .slerp(other.rotation.into(), t)
+                .into(),
+            translation: DVec3::from(self.translation)
+                .lerp(other.translation.into(), t)
+                .into(),
+        }
+    }
+
+    /// Composes the scale, rotation and translation into a single column-major matrix,
+    /// in the order scale, then rotation, then translation.
+    ///
+    /// To apply the result of an interaction relative to the transform a target had when
+    /// the interaction started, see [`GizmoResult::apply_to`](crate::GizmoResult::apply_to).
+    ///
+    /// ```
+    /// # use transform_gizmo::math::{DQuat, DVec3, Mat4, Transform, Vec3};
+    /// let transform = Transform::from_scale_rotation_translation(
+    ///     DVec3::splat(2.0),
+    ///     DQuat::IDENTITY,
+    ///     DVec3::new(1.0, 2.0, 3.0),
+    /// );
+    ///
+    /// let matrix = Mat4::from(transform.to_mat4());
+    /// assert_eq!(matrix.transform_point3(Vec3::ONE), Vec3::new(3.0, 4.0, 5.0));
+    /// ```
+    pub fn to_mat4(&self) -> mint::ColumnMatrix4<f32> {
+        DMat4::from(*self).as_mat4().into()
+    }
+}
+
+impl From<DMat4> for Transform {
+    fn from(matrix: DMat4) -> Self {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+        Self::from_scale_rotation_translation(scale, rotation, translation)
+    }
+}
+
+impl From<Mat4> for Transform {
+    fn from(matrix: Mat4) -> Self {
+        matrix.as_dmat4().into()
+    }
+}
+
+impl From<Transform> for DMat4 {
+    fn from(transform: Transform) -> Self {
+        Self::from_scale_rotation_translation(
+            transform.scale.into(),
+            transform.rotation.into(),
+            transform.translation.into(),
+        )
+    }
 }
 
 /// Creates a matrix that represents rotation between two 3d vectors
 ///
 /// Credit: <https://www.iquilezles.org/www/articles/noacos/noacos.htm>
-pub(crate) fn rotation_align(from: DVec3, to: DVec3) -> DMat3 {
+///
+/// Both vectors must be normalized. The result is undefined
+/// when the vectors point in exactly opposite directions.
+///
+/// ```
+/// # use transform_gizmo::math::{rotation_align, DMat3, DVec3};
+/// let rotation = rotation_align(DVec3::X, DVec3::Y);
+/// assert!((rotation * DVec3::X).abs_diff_eq(DVec3::Y, 1e-10));
+///
+/// // Aligning a vector with itself is the identity
+/// assert!(rotation_align(DVec3::Z, DVec3::Z).abs_diff_eq(DMat3::IDENTITY, 1e-10));
+/// ```
+pub fn rotation_align(from: DVec3, to: DVec3) -> DMat3 {
     let v = from.cross(to);
     let c = from.dot(to);
     let k = 1.0 / (1.0 + c);
@@ -57,7 +144,20 @@ pub(crate) fn rotation_align(from: DVec3, to: DVec3) -> DMat3 {
 /// This can be used to determine the shortest distance between those two rays.
 ///
 /// Credit: Practical Geometry Algorithms by Daniel Sunday: <http://geomalgorithms.com/code.html>
-pub(crate) fn ray_to_ray(a1: DVec3, adir: DVec3, b1: DVec3, bdir: DVec3) -> (f64, f64) {
+///
+/// Returns the distances along both rays to the closest points. The ray directions
+/// must be normalized. For parallel rays, the origin of the first ray is used.
+///
+/// ```
+/// # use transform_gizmo::math::{ray_to_ray, DVec3};
+/// let (ta, tb) = ray_to_ray(DVec3::ZERO, DVec3::X, DVec3::new(0.0, 1.0, 1.0), DVec3::Z);
+/// assert_eq!((ta, tb), (0.0, -1.0));
+///
+/// // Parallel rays
+/// let (ta, tb) = ray_to_ray(DVec3::ZERO, DVec3::X, DVec3::Y, DVec3::X);
+/// assert_eq!((ta, tb), (0.0, 0.0));
+/// ```
+pub fn ray_to_ray(a1: DVec3, adir: DVec3, b1: DVec3, bdir: DVec3) -> (f64, f64) {
     let b = adir.dot(bdir);
     let w = a1 - b1;
     let d = adir.dot(w);
@@ -81,7 +181,29 @@ pub(crate) fn ray_to_ray(a1: DVec3, adir: DVec3, b1: DVec3, bdir: DVec3) -> (f64
 /// This can be used to determine the shortest distance between those two segments.
 ///
 /// Credit: Practical Geometry Algorithms by Daniel Sunday: <http://geomalgorithms.com/code.html>
-pub(crate) fn segment_to_segment(a1: DVec3, a2: DVec3, b1: DVec3, b2: DVec3) -> (f64, f64) {
+///
+/// Returns the closest points as fractions between 0 and 1 along both segments.
+///
+/// ```
+/// # use transform_gizmo::math::{segment_to_segment, DVec3};
+/// let (ta, tb) = segment_to_segment(
+///     DVec3::new(-1.0, 0.0, 0.0),
+///     DVec3::new(1.0, 0.0, 0.0),
+///     DVec3::new(0.0, -1.0, 1.0),
+///     DVec3::new(0.0, 1.0, 1.0),
+/// );
+/// assert_eq!((ta, tb), (0.5, 0.5));
+///
+/// // Parallel segments
+/// let (ta, tb) = segment_to_segment(
+///     DVec3::ZERO,
+///     DVec3::X,
+///     DVec3::Y,
+///     DVec3::new(1.0, 1.0, 0.0),
+/// );
+/// assert_eq!((ta, tb), (0.0, 0.0));
+/// ```
+pub fn segment_to_segment(a1: DVec3, a2: DVec3, b1: DVec3, b2: DVec3) -> (f64, f64) {
     let da = a2 - a1;
     let db = b2 - b1;
     let la = da.length_squared();
@@ -164,7 +286,21 @@ pub(crate) fn intersect_plane(
 
 /// Finds the intersection point of a ray and a plane
 /// and distance from the intersection to the plane origin
-pub(crate) fn ray_to_plane_origin(
+///
+/// Returns the distance along the ray to the intersection and the distance from the
+/// intersection to the plane origin. If the ray does not hit the plane,
+/// the distance to the plane origin is [`f64::MAX`].
+///
+/// ```
+/// # use transform_gizmo::math::{ray_to_plane_origin, DVec3};
+/// let (t, dist) = ray_to_plane_origin(DVec3::Y, DVec3::ZERO, DVec3::new(1.0, 1.0, 0.0), -DVec3::Y);
+/// assert_eq!((t, dist), (1.0, 1.0));
+///
+/// // Ray parallel to the plane
+/// let (_, dist) = ray_to_plane_origin(DVec3::Y, DVec3::ZERO, DVec3::Y, DVec3::X);
+/// assert_eq!(dist, f64::MAX);
+/// ```
+pub fn ray_to_plane_origin(
     disc_normal: DVec3,
     disc_origin: DVec3,
     ray_origin: DVec3,
@@ -182,12 +318,71 @@ pub(crate) fn ray_to_plane_origin(
 }
 
 /// Rounds given value to the nearest interval
-pub(crate) fn round_to_interval(val: f64, interval: f64) -> f64 {
+///
+/// ```
+/// # use transform_gizmo::math::round_to_interval;
+/// assert_eq!(round_to_interval(0.26, 0.25), 0.25);
+/// assert_eq!(round_to_interval(-0.38, 0.25), -0.5);
+/// ```
+pub fn round_to_interval(val: f64, interval: f64) -> f64 {
     (val / interval).round() * interval
 }
 
+/// Clips a 3d line segment to the part that lies between the near and far
+/// clipping planes of the projection.
+///
+/// Returns [`None`] if no part of the segment is within the clipping planes.
+#[cfg(feature = "draw")]
+pub(crate) fn clip_segment(mvp: DMat4, from: DVec3, to: DVec3) -> Option<(DVec3, DVec3)> {
+    // Signed distances from a clip space point to the clipping planes.
+    // The point is inside when all of them are positive. The minimum w matches
+    // the threshold of `world_to_screen`, with some margin for rounding errors.
+    // The near plane of the depth range is covered by the w plane, so the depth
+    // is only limited to [-w, w], which works with any depth convention.
+    let plane_distances = |pos: DVec3| {
+        let pos = mvp * DVec4::from((pos, 1.0));
+        [pos.w - 1e-6, pos.w - pos.z, pos.w + pos.z]
+    };
+
+    let mut t_start = 0.0;
+    let mut t_end = 1.0;
+
+    for (from_dist, to_dist) in plane_distances(from).into_iter().zip(plane_distances(to)) {
+        if from_dist < 0.0 && to_dist < 0.0 {
+            return None;
+        }
+
+        let t = from_dist / (from_dist - to_dist);
+        if from_dist < 0.0 {
+            t_start = f64::max(t_start, t);
+        } else if to_dist < 0.0 {
+            t_end = f64::min(t_end, t);
+        }
+    }
+
+    if t_start > t_end {
+        return None;
+    }
+
+    Some((from.lerp(to, t_start), from.lerp(to, t_end)))
+}
+
 /// Calculates 2d screen coordinates from 3d world coordinates
-pub(crate) fn world_to_screen(viewport: Rect, mvp: DMat4, pos: DVec3) -> Option<Pos2> {
+///
+/// Returns [`None`] for points behind the camera.
+///
+/// ```
+/// # use transform_gizmo::math::{world_to_screen, DMat4, DVec3, Pos2, Rect};
+/// let viewport = Rect::from_min_max(Pos2::ZERO, Pos2::new(100.0, 100.0));
+/// let projection = DMat4::perspective_rh(std::f64::consts::FRAC_PI_2, 1.0, 0.1, 100.0);
+///
+/// let in_front = world_to_screen(viewport, projection, DVec3::new(0.0, 0.0, -1.0));
+/// assert_eq!(in_front, Some(Pos2::new(50.0, 50.0)));
+///
+/// let behind = world_to_screen(viewport, projection, DVec3::new(0.0, 0.0, 1.0));
+/// assert_eq!(behind, None);
+/// ```
+pub fn world_to_screen(viewport: Rect, mvp: DMat4, pos: DVec3) -> Option<Pos2> {
     let mut pos = mvp * DVec4::from((pos, 1.0));
 
     if pos.w < 1e-10 {
@@ -206,7 +401,21 @@ pub(crate) fn world_to_screen(viewport: Rect, mvp: DMat4, pos: DVec3) -> Option<
 }
 
 /// Calculates 3d world coordinates from 2d screen coordinates
-pub(crate) fn screen_to_world(viewport: Rect, mat: DMat4, pos: Pos2, z: f64) -> DVec3 {
+///
+/// `mat` is the inverse of the view-projection matrix and `z`
+/// is the depth of the point in normalized device coordinates.
+///
+/// ```
+/// # use transform_gizmo::math::{screen_to_world, DMat4, DVec3, Pos2, Rect};
+/// let viewport = Rect::from_min_max(Pos2::ZERO, Pos2::new(100.0, 100.0));
+///
+/// let center = screen_to_world(viewport, DMat4::IDENTITY, Pos2::new(50.0, 50.0), 0.0);
+/// assert_eq!(center, DVec3::ZERO);
+///
+/// let top_right = screen_to_world(viewport, DMat4::IDENTITY, Pos2::new(100.0, 0.0), 0.0);
+/// assert_eq!(top_right, DVec3::new(1.0, 1.0, 0.0));
+/// ```
+pub fn screen_to_world(viewport: Rect, mat: DMat4, pos: Pos2, z: f64) -> DVec3 {
     let x = (((pos.x - viewport.min.x) / viewport.width()) * 2.0 - 1.0) as f64;
     let y = (((pos.y - viewport.min.y) / viewport.height()) * 2.0 - 1.0) as f64;
 
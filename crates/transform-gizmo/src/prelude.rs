@@ -1,5 +1,9 @@
-pub use crate::config::{GizmoConfig, GizmoDirection, GizmoMode, GizmoOrientation, GizmoVisuals};
-pub use crate::gizmo::{Gizmo, GizmoDrawData, GizmoInteraction, GizmoResult};
+pub use crate::config::{
+    AngleUnit, GizmoConfig, GizmoConfigBuilder, GizmoDirection, GizmoMode, GizmoOrientation,
+    GizmoVisuals, Handedness, HandleShape, HighlightMode, PlaneFill, RotationInputMode, SizeMode,
+    TransformKind,
+};
+pub use crate::gizmo::{Gizmo, GizmoDrawData, GizmoHit, GizmoInteraction, GizmoResult, Ray};
 
 pub use enumset::{enum_set, EnumSet};
 
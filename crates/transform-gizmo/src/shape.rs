@@ -1,30 +1,42 @@
 use std::f64::consts::TAU;
 
-use crate::math::{Pos2, Rect};
-use ecolor::Color32;
+use crate::math::{Pos2, Rect, Vec2};
+use ecolor::{Color32, Rgba};
 use epaint::{Mesh, TessellationOptions, Tessellator, TextureId};
 pub(crate) use epaint::{Shape, Stroke};
 use glam::{DMat4, DVec3};
 
-use crate::math::world_to_screen;
-
-const STEPS_PER_RAD: f64 = 20.0;
+use crate::math::{clip_segment, world_to_screen};
 
 pub(crate) struct ShapeBuidler {
     mvp: DMat4,
     viewport: Rect,
     pixels_per_point: f32,
+    circle_segments: usize,
 }
 
 impl ShapeBuidler {
-    pub(crate) fn new(mvp: DMat4, viewport: Rect, pixels_per_point: f32) -> Self {
+    pub(crate) fn new(
+        mvp: DMat4,
+        viewport: Rect,
+        pixels_per_point: f32,
+        circle_segments: usize,
+    ) -> Self {
         Self {
             mvp,
             viewport,
             pixels_per_point,
+            circle_segments,
         }
     }
 
+    /// Number of points used for drawing an arc of the given angle
+    fn steps(&self, angle: f64) -> usize {
+        (self.circle_segments as f64 * angle.abs() / TAU)
+            .ceil()
+            .max(1.0) as usize
+    }
+
     fn tessellate_shape(&self, shape: Shape) -> Mesh {
         let mut tessellator = Tessellator::new(
             self.pixels_per_point,
@@ -46,7 +58,7 @@ impl ShapeBuidler {
     fn arc_points(&self, radius: f64, start_angle: f64, end_angle: f64) -> Vec<Pos2> {
         let angle = f64::clamp(end_angle - start_angle, -TAU, TAU);
 
-        let step_count = steps(angle);
+        let step_count = self.steps(angle);
         let mut points = Vec::with_capacity(step_count);
 
         let step_size = angle / (step_count - 1) as f64;
@@ -87,6 +99,38 @@ impl ShapeBuidler {
         })
     }
 
+    /// Draws an arc with its color changing linearly from `start_color` to `end_color`.
+    pub(crate) fn gradient_arc(
+        &self,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+        width: f32,
+        start_color: Color32,
+        end_color: Color32,
+    ) -> Mesh {
+        let points = self.arc_points(radius, start_angle, end_angle);
+        let segment_count = points.len().saturating_sub(1).max(1);
+
+        let (start_color, end_color) = (Rgba::from(start_color), Rgba::from(end_color));
+
+        let segments = points
+            .windows(2)
+            .enumerate()
+            .map(|(i, segment)| {
+                let t = (i as f32 + 0.5) / segment_count as f32;
+                let color = start_color * (1.0 - t) + end_color * t;
+
+                Shape::LineSegment {
+                    points: [segment[0], segment[1]],
+                    stroke: Stroke::new(width, color),
+                }
+            })
+            .collect();
+
+        self.tessellate_shape(Shape::Vec(segments))
+    }
+
     pub(crate) fn circle(&self, radius: f64, stroke: impl Into<Stroke>) -> Mesh {
         self.arc(radius, 0.0, TAU, stroke)
     }
@@ -103,16 +147,25 @@ impl ShapeBuidler {
         self.tessellate_shape(Shape::convex_polygon(points, color, stroke.into()))
     }
 
+    /// Clips the line segment to the part that is between the near and far planes.
+    pub(crate) fn clip_segment(&self, from: DVec3, to: DVec3) -> Option<(DVec3, DVec3)> {
+        clip_segment(self.mvp, from, to)
+    }
+
+    /// Screen positions of the line segment, clipped between the near and far planes.
+    fn segment_points(&self, from: DVec3, to: DVec3) -> Option<[Pos2; 2]> {
+        let (from, to) = self.clip_segment(from, to)?;
+
+        Some([
+            world_to_screen(self.viewport, self.mvp, from)?,
+            world_to_screen(self.viewport, self.mvp, to)?,
+        ])
+    }
+
     pub(crate) fn line_segment(&self, from: DVec3, to: DVec3, stroke: impl Into<Stroke>) -> Mesh {
-        let mut points: [Pos2; 2] = Default::default();
-
-        for (i, point) in points.iter_mut().enumerate() {
-            if let Some(pos) = world_to_screen(self.viewport, self.mvp, [from, to][i]) {
-                *point = pos;
-            } else {
-                return Mesh::default();
-            }
-        }
+        let Some(points) = self.segment_points(from, to) else {
+            return Mesh::default();
+        };
 
         self.tessellate_shape(Shape::LineSegment {
             points,
@@ -120,6 +173,30 @@ impl ShapeBuidler {
         })
     }
 
+    /// Line segment drawn with dashes, with the dashes and the gaps between them
+    /// given in pixels. Without a dash length, a solid line segment is drawn.
+    pub(crate) fn dashed_line_segment(
+        &self,
+        from: DVec3,
+        to: DVec3,
+        stroke: impl Into<Stroke>,
+        dash_length: Option<f32>,
+    ) -> Mesh {
+        let Some(dash_length) = dash_length.filter(|length| *length > 0.0) else {
+            return self.line_segment(from, to, stroke);
+        };
+        let Some(points) = self.segment_points(from, to) else {
+            return Mesh::default();
+        };
+
+        self.tessellate_shape(Shape::Vec(Shape::dashed_line(
+            &points,
+            stroke,
+            dash_length,
+            dash_length,
+        )))
+    }
+
     pub(crate) fn arrow(&self, from: DVec3, to: DVec3, stroke: impl Into<Stroke>) -> Mesh {
         let stroke = stroke.into();
         let arrow_start = world_to_screen(self.viewport, self.mvp, from);
@@ -138,6 +215,40 @@ impl ShapeBuidler {
         })
     }
 
+    /// Filled circle facing the screen, with the radius given in pixels.
+    pub(crate) fn dot(&self, center: DVec3, radius: f32, color: Color32) -> Mesh {
+        self.tessellate_shape(
+            world_to_screen(self.viewport, self.mvp, center).map_or(Shape::Noop, |center| {
+                Shape::circle_filled(center, radius, color)
+            }),
+        )
+    }
+
+    /// Line segments facing the screen. The segment points are given in pixels,
+    /// relative to the projected `anchor`.
+    pub(crate) fn screen_segments(
+        &self,
+        anchor: DVec3,
+        segments: &[[Vec2; 2]],
+        stroke: impl Into<Stroke>,
+    ) -> Mesh {
+        let stroke = stroke.into();
+
+        self.tessellate_shape(world_to_screen(self.viewport, self.mvp, anchor).map_or(
+            Shape::Noop,
+            |anchor| {
+                Shape::Vec(
+                    segments
+                        .iter()
+                        .map(|[from, to]| {
+                            Shape::line_segment([anchor + *from, anchor + *to], stroke)
+                        })
+                        .collect(),
+                )
+            },
+        ))
+    }
+
     pub(crate) fn polygon(
         &self,
         points: &[DVec3],
@@ -178,7 +289,7 @@ impl ShapeBuidler {
         stroke: impl Into<Stroke>,
     ) -> Mesh {
         let angle_delta = end_angle - start_angle;
-        let step_count = steps(angle_delta.abs());
+        let step_count = self.steps(angle_delta.abs());
 
         if step_count < 2 {
             return Mesh::default();
@@ -222,7 +333,3 @@ impl ShapeBuidler {
         world_to_screen(self.viewport, self.mvp, vec)
     }
 }
-
-fn steps(angle: f64) -> usize {
-    (STEPS_PER_RAD * angle.abs()).ceil().max(1.0) as usize
-}
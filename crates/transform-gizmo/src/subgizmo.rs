@@ -5,7 +5,10 @@ use std::ops::Deref;
 
 use enum_dispatch::enum_dispatch;
 
-use crate::{config::PreparedGizmoConfig, gizmo::Ray, GizmoDrawData, GizmoResult};
+use crate::config::{GizmoDirection, GizmoMode, PreparedGizmoConfig, SnapFunctions, TransformKind};
+#[cfg(feature = "draw")]
+use crate::GizmoDrawData;
+use crate::{gizmo::Ray, GizmoResult};
 
 pub(crate) use arcball::ArcballSubGizmo;
 pub(crate) use rotation::RotationSubGizmo;
@@ -20,7 +23,8 @@ pub(crate) mod translation;
 
 #[derive(Clone, Debug)]
 /// Enumeration of different subgizmo types.
-#[enum_dispatch(SubGizmoControl)]
+#[cfg_attr(feature = "draw", enum_dispatch(SubGizmoControl, SubGizmoDraw))]
+#[cfg_attr(not(feature = "draw"), enum_dispatch(SubGizmoControl))]
 pub(crate) enum SubGizmo {
     Rotate(RotationSubGizmo),
     Translate(TranslationSubGizmo),
@@ -32,6 +36,12 @@ pub(crate) enum SubGizmo {
 pub(crate) trait SubGizmoControl {
     /// Unique identifier for this subgizmo.
     fn id(&self) -> u64;
+    /// Gizmo mode this subgizmo belongs to.
+    fn mode(&self) -> GizmoMode;
+    /// Direction this subgizmo operates in.
+    fn direction(&self) -> GizmoDirection;
+    /// Kind of constraint this subgizmo transforms with.
+    fn transform_kind(&self) -> TransformKind;
     /// Update the configuration used by the gizmo.
     fn update_config(&mut self, config: PreparedGizmoConfig);
     /// Sets whether this subgizmo is currently focused.
@@ -42,11 +52,18 @@ pub(crate) trait SubGizmoControl {
     fn is_focused(&self) -> bool;
     /// Returns true if this subgizmo is currently active.
     fn is_active(&self) -> bool;
+    /// Clears the interaction state of this subgizmo.
+    fn reset(&mut self);
     /// Pick the subgizmo based on pointer ray. If it is close enough to
     /// the mouse pointer, distance from camera to the subgizmo is returned.
     fn pick(&mut self, ray: Ray) -> Option<f64>;
     /// Update the subgizmo based on pointer ray and interaction.
-    fn update(&mut self, ray: Ray) -> Option<GizmoResult>;
+    fn update(&mut self, ray: Ray, snap_fns: &SnapFunctions) -> Option<GizmoResult>;
+}
+
+#[cfg(feature = "draw")]
+#[enum_dispatch]
+pub(crate) trait SubGizmoDraw {
     /// Draw the subgizmo.
     fn draw(&self) -> GizmoDrawData;
 }
@@ -55,12 +72,26 @@ pub(crate) trait SubGizmoKind: 'static {
     type Params: Debug + Copy + Hash;
     type State: Debug + Copy + Clone + Send + Sync + Default + 'static;
 
+    /// Gizmo mode subgizmos of this kind belong to.
+    const MODE: GizmoMode;
+
+    fn direction(subgizmo: &SubGizmoConfig<Self>) -> GizmoDirection
+    where
+        Self: Sized;
+    fn transform_kind(subgizmo: &SubGizmoConfig<Self>) -> TransformKind
+    where
+        Self: Sized;
     fn pick(subgizmo: &mut SubGizmoConfig<Self>, ray: Ray) -> Option<f64>
     where
         Self: Sized;
-    fn update(subgizmo: &mut SubGizmoConfig<Self>, ray: Ray) -> Option<GizmoResult>
+    fn update(
+        subgizmo: &mut SubGizmoConfig<Self>,
+        ray: Ray,
+        snap_fns: &SnapFunctions,
+    ) -> Option<GizmoResult>
     where
         Self: Sized;
+    #[cfg(feature = "draw")]
     fn draw(subgizmo: &SubGizmoConfig<Self>) -> GizmoDrawData
     where
         Self: Sized;
@@ -122,6 +153,19 @@ where
     fn id(&self) -> u64 {
         self.id
     }
+
+    fn mode(&self) -> GizmoMode {
+        T::MODE
+    }
+
+    fn direction(&self) -> GizmoDirection {
+        T::direction(self)
+    }
+
+    fn transform_kind(&self) -> TransformKind {
+        T::transform_kind(self)
+    }
+
     fn update_config(&mut self, config: PreparedGizmoConfig) {
         self.config = config;
     }
@@ -142,14 +186,26 @@ where
         self.active
     }
 
+    fn reset(&mut self) {
+        self.focused = false;
+        self.active = false;
+        self.state = Default::default();
+    }
+
     fn pick(&mut self, ray: Ray) -> Option<f64> {
         T::pick(self, ray)
     }
 
-    fn update(&mut self, ray: Ray) -> Option<GizmoResult> {
-        T::update(self, ray)
+    fn update(&mut self, ray: Ray, snap_fns: &SnapFunctions) -> Option<GizmoResult> {
+        T::update(self, ray, snap_fns)
     }
+}
 
+#[cfg(feature = "draw")]
+impl<T> SubGizmoDraw for SubGizmoConfig<T>
+where
+    T: SubGizmoKind,
+{
     fn draw(&self) -> GizmoDrawData {
         T::draw(self)
     }
@@ -1,7 +1,13 @@
+use crate::config::{PreparedGizmoConfig, SnapFunctions};
 use crate::math::{screen_to_world, DQuat, Pos2};
-use crate::subgizmo::common::{draw_circle, pick_circle};
-use crate::subgizmo::{SubGizmoConfig, SubGizmoKind};
-use crate::{config::PreparedGizmoConfig, gizmo::Ray, GizmoDrawData, GizmoResult};
+#[cfg(feature = "draw")]
+use crate::subgizmo::common::draw_circle;
+use crate::subgizmo::common::pick_circle;
+use crate::subgizmo::{common::TransformKind, SubGizmoConfig, SubGizmoKind};
+#[cfg(feature = "draw")]
+use crate::GizmoDrawData;
+use crate::{gizmo::Ray, GizmoDirection, GizmoMode, GizmoResult};
+#[cfg(feature = "draw")]
 use ecolor::Color32;
 
 pub(crate) type ArcballSubGizmo = SubGizmoConfig<Arcball>;
@@ -19,6 +25,17 @@ impl SubGizmoKind for Arcball {
     type Params = ();
     type State = ArcballState;
 
+    const MODE: GizmoMode = GizmoMode::Rotate;
+
+    fn direction(_subgizmo: &ArcballSubGizmo) -> GizmoDirection {
+        // The arcball rotates freely in view space.
+        GizmoDirection::View
+    }
+
+    fn transform_kind(_subgizmo: &ArcballSubGizmo) -> TransformKind {
+        TransformKind::Plane
+    }
+
     fn pick(subgizmo: &mut ArcballSubGizmo, ray: Ray) -> Option<f64> {
         let pick_result = pick_circle(
             &subgizmo.config,
@@ -35,7 +52,11 @@ impl SubGizmoKind for Arcball {
         Some(f64::MAX)
     }
 
-    fn update(subgizmo: &mut ArcballSubGizmo, ray: Ray) -> Option<GizmoResult> {
+    fn update(
+        subgizmo: &mut ArcballSubGizmo,
+        ray: Ray,
+        _snap_fns: &SnapFunctions,
+    ) -> Option<GizmoResult> {
         let dir = ray.screen_pos - subgizmo.state.last_pos;
 
         let rotation_delta = if dir.length_sq() > f32::EPSILON {
@@ -47,7 +68,9 @@ impl SubGizmoKind for Arcball {
             let a = (a - origin).normalize();
             let b = (b - origin).normalize();
 
-            DQuat::from_axis_angle(a.cross(b).normalize(), a.dot(b).acos() * 10.0)
+            let angle = a.dot(b).acos() * 10.0 * subgizmo.config.motion_scale();
+
+            DQuat::from_axis_angle(a.cross(b).normalize(), angle)
         } else {
             DQuat::IDENTITY
         };
@@ -61,6 +84,7 @@ impl SubGizmoKind for Arcball {
         })
     }
 
+    #[cfg(feature = "draw")]
     fn draw(subgizmo: &ArcballSubGizmo) -> GizmoDrawData {
         draw_circle(
             &subgizmo.config,
@@ -73,5 +97,6 @@ impl SubGizmoKind for Arcball {
 
 /// Radius to use for outer circle subgizmos
 pub(crate) fn arcball_radius(config: &PreparedGizmoConfig) -> f64 {
-    (config.scale_factor * (config.visuals.gizmo_size + config.visuals.stroke_width - 5.0)) as f64
+    (config.scale_factor * config.visuals.gizmo_size
+        + config.pixel_scale_factor * (config.visuals.stroke_width - 5.0)) as f64
 }
@@ -1,20 +1,35 @@
-use crate::math::{ray_to_plane_origin, segment_to_segment};
+#[cfg(feature = "draw")]
+use crate::math::Vec2;
+use crate::math::{ray_to_plane_origin, segment_to_segment, world_to_screen};
 use crate::GizmoMode;
+#[cfg(feature = "draw")]
 use ecolor::Color32;
-use std::ops::{Add, RangeInclusive};
+#[cfg(feature = "draw")]
+use std::ops::Add;
+use std::ops::RangeInclusive;
 
+#[cfg(feature = "draw")]
+use crate::config::{HandleShape, HighlightMode, PlaneFill};
+#[cfg(feature = "draw")]
 use crate::shape::ShapeBuidler;
-use crate::{config::PreparedGizmoConfig, gizmo::Ray, GizmoDirection, GizmoDrawData};
-use glam::{DMat3, DMat4, DQuat, DVec3};
+#[cfg(feature = "draw")]
+use crate::GizmoDrawData;
+use crate::{config::PreparedGizmoConfig, gizmo::Ray, GizmoDirection};
+use glam::DVec3;
+#[cfg(feature = "draw")]
+use glam::{DMat3, DMat4, DQuat};
 
 const ARROW_FADE: RangeInclusive<f64> = 0.95..=0.99;
 const PLANE_FADE: RangeInclusive<f64> = 0.70..=0.86;
+/// Lower limit for scaling the focus distance of foreshortened arrows
+const MIN_ARROW_FOCUS_SCALE: f64 = 0.5;
+/// Number of diagonal lines in hatched planes
+#[cfg(feature = "draw")]
+const PLANE_HATCH_LINES: usize = 5;
+/// Lower limit for the foreshortening used when extending arrows to their minimum screen length
+const MIN_ARROW_FORESHORTENING: f64 = 0.1;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-pub(crate) enum TransformKind {
-    Axis,
-    Plane,
-}
+pub(crate) use crate::config::TransformKind;
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct PickResult {
@@ -31,10 +46,18 @@ struct ArrowParams {
     length: f64,
 }
 
-fn arrow_params(config: &PreparedGizmoConfig, direction: DVec3, mode: GizmoMode) -> ArrowParams {
-    let width = (config.scale_factor * config.visuals.stroke_width) as f64;
+/// Computes the arrow geometry along `direction`. `world_direction` is the same
+/// direction in world space, used to keep the arrow at its minimum screen length.
+fn arrow_params(
+    config: &PreparedGizmoConfig,
+    direction: DVec3,
+    world_direction: DVec3,
+    mode: GizmoMode,
+) -> ArrowParams {
+    let width = (config.pixel_scale_factor * config.visuals.stroke_width) as f64;
 
-    let (start, length) = if mode == GizmoMode::Translate && config.modes.contains(GizmoMode::Scale)
+    let (start, mut length) = if mode == GizmoMode::Translate
+        && config.modes.contains(GizmoMode::Scale)
     {
         // Modes contain both translate and scale. Use a bit different translate arrow, so the modes do not overlap.
         let length = (config.scale_factor * config.visuals.gizmo_size) as f64;
@@ -54,6 +77,16 @@ fn arrow_params(config: &PreparedGizmoConfig, direction: DVec3, mode: GizmoMode)
         (start, length)
     };
 
+    if let Some(min_screen_length) = config.visuals.min_arrow_screen_length {
+        // Arrows pointing towards the camera are foreshortened on the screen.
+        let dot = config.eye_to_model_dir.dot(world_direction);
+        let foreshortening = (1.0 - dot * dot).max(0.0).sqrt();
+        let min_length = (min_screen_length * config.pixel_scale_factor) as f64
+            / foreshortening.max(MIN_ARROW_FORESHORTENING);
+
+        length = length.max(min_length);
+    }
+
     ArrowParams {
         start,
         end: start + direction * length,
@@ -62,17 +95,53 @@ fn arrow_params(config: &PreparedGizmoConfig, direction: DVec3, mode: GizmoMode)
     }
 }
 
+/// Directions to draw the arrows of an axis in.
+/// Includes the negative direction if the arrows are mirrored.
+#[cfg(feature = "draw")]
+fn arrow_directions(
+    config: &PreparedGizmoConfig,
+    direction: DVec3,
+    mode: GizmoMode,
+) -> impl Iterator<Item = DVec3> {
+    let mirrored = config.visuals.mirrored_arrows
+        || (mode == GizmoMode::Scale && config.visuals.mirrored_scale_handles);
+
+    std::iter::once(direction).chain(mirrored.then_some(-direction))
+}
+
 pub(crate) fn pick_arrow(
     config: &PreparedGizmoConfig,
     ray: Ray,
     direction: GizmoDirection,
     mode: GizmoMode,
 ) -> PickResult {
-    let ray_length = 1e+14;
-
     let direction = gizmo_normal(config, direction);
 
-    let mut arrow_params = arrow_params(config, direction, mode);
+    let (mut result, dist) = pick_arrow_direction(config, ray, direction, mode);
+
+    if config.visuals.mirrored_arrows {
+        let (mirrored_result, mirrored_dist) = pick_arrow_direction(config, ray, -direction, mode);
+
+        // Prefer the arrow that is closest to the pointer ray.
+        if mirrored_result.picked && (!result.picked || mirrored_dist < dist) {
+            result = mirrored_result;
+        }
+    }
+
+    result
+}
+
+/// Picks a single arrow pointing in the given world space direction.
+/// Returns the pick result and the distance from the pointer ray to the arrow.
+fn pick_arrow_direction(
+    config: &PreparedGizmoConfig,
+    ray: Ray,
+    direction: DVec3,
+    mode: GizmoMode,
+) -> (PickResult, f64) {
+    let ray_length = 1e+14;
+
+    let mut arrow_params = arrow_params(config, direction, direction, mode);
     arrow_params.start += config.translation;
     arrow_params.end += config.translation;
 
@@ -93,14 +162,30 @@ pub(crate) fn pick_arrow(
     let visibility =
         (1.0 - (dot - *ARROW_FADE.start()) / (*ARROW_FADE.end() - *ARROW_FADE.start())).min(1.0);
 
-    let picked = visibility > 0.0 && dist <= config.focus_distance as f64;
-
-    PickResult {
-        subgizmo_point,
-        visibility,
-        picked,
-        t: ray_t,
-    }
+    // Arrows pointing towards the camera look shorter on the screen,
+    // so they are made proportionally harder to grab.
+    let screen_length =
+        world_to_screen(config.viewport, config.view_projection, arrow_params.start)
+            .zip(world_to_screen(
+                config.viewport,
+                config.view_projection,
+                arrow_params.end,
+            ))
+            .map_or(0.0, |(start, end)| start.distance(end) as f64);
+    let full_screen_length = arrow_params.length / config.pixel_size as f64;
+    let focus_scale = (screen_length / full_screen_length).clamp(MIN_ARROW_FOCUS_SCALE, 1.0);
+
+    let picked = visibility > 0.0 && dist <= config.focus_distance as f64 * focus_scale;
+
+    (
+        PickResult {
+            subgizmo_point,
+            visibility,
+            picked,
+            t: ray_t,
+        },
+        dist,
+    )
 }
 
 pub(crate) fn pick_plane(
@@ -162,6 +247,31 @@ pub(crate) fn pick_circle(
     }
 }
 
+/// Size of the axis labels, relative to the stroke width
+#[cfg(feature = "draw")]
+const AXIS_LABEL_SIZE: f32 = 3.0;
+
+/// Line segments of the axis label letters, within a unit square centered at the origin.
+/// The y axis points down, as on the screen.
+#[cfg(feature = "draw")]
+fn axis_label_segments(direction: GizmoDirection) -> &'static [[(f32, f32); 2]] {
+    match direction {
+        GizmoDirection::X => &[[(-0.5, -0.5), (0.5, 0.5)], [(-0.5, 0.5), (0.5, -0.5)]],
+        GizmoDirection::Y => &[
+            [(-0.5, -0.5), (0.0, 0.0)],
+            [(0.5, -0.5), (0.0, 0.0)],
+            [(0.0, 0.0), (0.0, 0.5)],
+        ],
+        GizmoDirection::Z => &[
+            [(-0.5, -0.5), (0.5, -0.5)],
+            [(0.5, -0.5), (-0.5, 0.5)],
+            [(-0.5, 0.5), (0.5, 0.5)],
+        ],
+        GizmoDirection::View => &[],
+    }
+}
+
+#[cfg(feature = "draw")]
 pub(crate) fn draw_arrow(
     config: &PreparedGizmoConfig,
     opacity: f32,
@@ -185,49 +295,114 @@ pub(crate) fn draw_arrow(
         config.view_projection * transform,
         config.viewport,
         config.pixels_per_point,
+        config.visuals.circle_segments,
     );
 
-    let direction = gizmo_local_normal(config, direction);
+    let label_size = AXIS_LABEL_SIZE * config.visuals.stroke_width;
+    let label_segments = axis_label_segments(direction)
+        .iter()
+        .map(|&[from, to]| [Vec2::from(from) * label_size, Vec2::from(to) * label_size])
+        .collect::<Vec<_>>();
 
-    let arrow_params = arrow_params(config, direction, mode);
+    let direction = gizmo_local_normal(config, direction);
 
     let tip_stroke_width = 2.4 * config.visuals.stroke_width;
-    let tip_length = (tip_stroke_width * config.scale_factor) as f64;
+    let tip_length = (tip_stroke_width * config.pixel_scale_factor) as f64;
+
+    let handle_shape = match mode {
+        GizmoMode::Translate => Some(config.visuals.translate_handle),
+        GizmoMode::Scale => Some(config.visuals.scale_handle),
+        GizmoMode::Rotate => None,
+    };
 
-    let tip_start = arrow_params.end - arrow_params.direction * tip_length;
+    // Scale arrows of mirrored axes point inwards.
+    let inward = mode == GizmoMode::Scale && config.scale.dot(direction) < 0.0;
 
     let mut draw_data = GizmoDrawData::default();
-    draw_data = draw_data.add(
-        shape_builder
-            .line_segment(
-                arrow_params.start,
-                tip_start,
-                (config.visuals.stroke_width, color),
-            )
-            .into(),
-    );
 
-    match mode {
-        GizmoMode::Scale => {
-            draw_data = draw_data.add(
-                shape_builder
-                    .line_segment(tip_start, arrow_params.end, (tip_stroke_width, color))
-                    .into(),
-            );
+    for arrow_dir in arrow_directions(config, direction, mode) {
+        let world_direction = if config.local_space() {
+            config.rotation * arrow_dir
+        } else {
+            arrow_dir
+        };
+        let arrow_params = arrow_params(config, arrow_dir, world_direction, mode);
+
+        // Shorten arrows that extend past the clipping planes,
+        // so that the arrow head stays visible.
+        let Some((start, end)) = shape_builder.clip_segment(arrow_params.start, arrow_params.end)
+        else {
+            continue;
+        };
+
+        let tip_start = end - arrow_params.direction * tip_length.min(start.distance(end));
+
+        // `eye_to_model_dir` points from the gizmo towards the camera.
+        let dash_length = config
+            .visuals
+            .far_arrow_dash_length
+            .filter(|_| config.eye_to_model_dir.dot(world_direction) < 0.0);
+
+        draw_data = draw_data.add(
+            shape_builder
+                .dashed_line_segment(
+                    start,
+                    tip_start,
+                    (config.visuals.stroke_width, color),
+                    dash_length,
+                )
+                .into(),
+        );
+
+        match handle_shape {
+            Some(HandleShape::Arrow) => {
+                let (from, to) = if inward {
+                    (end, tip_start)
+                } else {
+                    (tip_start, end)
+                };
+                draw_data = draw_data.add(
+                    shape_builder
+                        .arrow(from, to, (tip_stroke_width, color))
+                        .into(),
+                );
+            }
+            Some(HandleShape::Square) => {
+                draw_data = draw_data.add(
+                    shape_builder
+                        .line_segment(tip_start, end, (tip_stroke_width, color))
+                        .into(),
+                );
+            }
+            Some(HandleShape::Circle) => {
+                draw_data = draw_data.add(
+                    shape_builder
+                        .dot(tip_start.lerp(end, 0.5), tip_stroke_width * 0.5, color)
+                        .into(),
+                );
+            }
+            None => {}
         }
-        GizmoMode::Translate => {
+
+        // Only the arrow along the positive axis is labeled
+        if config.visuals.show_axis_labels && handle_shape.is_some() && arrow_dir == direction {
+            let label_offset = (label_size * config.pixel_scale_factor) as f64;
             draw_data = draw_data.add(
                 shape_builder
-                    .arrow(tip_start, arrow_params.end, (tip_stroke_width, color))
+                    .screen_segments(
+                        end + arrow_params.direction * label_offset,
+                        &label_segments,
+                        ((config.visuals.stroke_width * 0.5).max(1.0), color),
+                    )
                     .into(),
             );
         }
-        GizmoMode::Rotate => {}
     }
 
     draw_data
 }
 
+#[cfg(feature = "draw")]
 pub(crate) fn draw_plane(
     config: &PreparedGizmoConfig,
     opacity: f32,
@@ -250,6 +425,7 @@ pub(crate) fn draw_plane(
         config.view_projection * transform,
         config.viewport,
         config.pixels_per_point,
+        config.visuals.circle_segments,
     );
 
     let scale = plane_size(config) * 0.5;
@@ -257,6 +433,20 @@ pub(crate) fn draw_plane(
     let b = plane_tangent(direction) * scale;
     let origin = plane_local_origin(config, direction);
 
+    let hatched = config.visuals.plane_fill == PlaneFill::Hatched;
+    let thin_stroke_width = config.visuals.stroke_width * 0.5;
+
+    let border = if focused && config.visuals.plane_hover_border {
+        let border_color = brighten(gizmo_color(config, true, direction)).gamma_multiply(opacity);
+        (thin_stroke_width, border_color)
+    } else if hatched {
+        (thin_stroke_width, color)
+    } else {
+        (0.0, Color32::TRANSPARENT)
+    };
+
+    let fill = if hatched { Color32::TRANSPARENT } else { color };
+
     let mut draw_data = GizmoDrawData::default();
     draw_data = draw_data.add(
         shape_builder
@@ -267,14 +457,36 @@ pub(crate) fn draw_plane(
                     origin + b + a,
                     origin - b + a,
                 ],
-                color,
-                (0.0, Color32::TRANSPARENT),
+                fill,
+                border,
             )
             .into(),
     );
+
+    if hatched {
+        // Lines where u - v is constant, for points origin + b * u + a * v
+        // on the plane, with u and v going from -1 to 1.
+        for i in 0..PLANE_HATCH_LINES {
+            let c = -2.0 + 4.0 * (i as f64 + 0.5) / PLANE_HATCH_LINES as f64;
+            let (start_u, start_v) = ((c - 1.0).max(-1.0), (-c - 1.0).max(-1.0));
+            let (end_u, end_v) = ((c + 1.0).min(1.0), (1.0 - c).min(1.0));
+
+            draw_data = draw_data.add(
+                shape_builder
+                    .line_segment(
+                        origin + b * start_u + a * start_v,
+                        origin + b * end_u + a * end_v,
+                        (thin_stroke_width, color),
+                    )
+                    .into(),
+            );
+        }
+    }
+
     draw_data
 }
 
+#[cfg(feature = "draw")]
 pub(crate) fn draw_circle(
     config: &PreparedGizmoConfig,
     color: Color32,
@@ -299,6 +511,7 @@ pub(crate) fn draw_circle(
         config.view_projection * transform,
         config.viewport,
         config.pixels_per_point,
+        config.visuals.circle_segments,
     );
 
     let mut draw_data = GizmoDrawData::default();
@@ -318,6 +531,46 @@ pub(crate) fn draw_circle(
     draw_data
 }
 
+/// Draws the position of the gizmo projected onto the ground plane,
+/// along with a line from the gizmo down to the ground.
+#[cfg(feature = "draw")]
+pub(crate) fn draw_ground_shadow(
+    config: &PreparedGizmoConfig,
+    ground_height: f64,
+) -> GizmoDrawData {
+    const SHADOW_RADIUS_FACTOR: f64 = 0.25;
+
+    let ground_point = DVec3::new(config.translation.x, ground_height, config.translation.z);
+    let radius = (config.scale_factor * config.visuals.gizmo_size) as f64 * SHADOW_RADIUS_FACTOR;
+    let color = config.visuals.guide_color;
+    let stroke = (config.visuals.guide_stroke_width, color);
+
+    // Shapes are drawn on the XZ plane, which is parallel to the ground.
+    let ground_shape_builder = ShapeBuidler::new(
+        config.view_projection * DMat4::from_translation(ground_point),
+        config.viewport,
+        config.pixels_per_point,
+        config.visuals.circle_segments,
+    );
+    let shape_builder = ShapeBuidler::new(
+        config.view_projection,
+        config.viewport,
+        config.pixels_per_point,
+        config.visuals.circle_segments,
+    );
+
+    GizmoDrawData::from(ground_shape_builder.filled_circle(
+        radius,
+        color.gamma_multiply(0.25),
+        stroke,
+    ))
+    .add(
+        shape_builder
+            .line_segment(config.translation, ground_point, stroke)
+            .into(),
+    )
+}
+
 pub(crate) const fn plane_bitangent(direction: GizmoDirection) -> DVec3 {
     match direction {
         GizmoDirection::X => DVec3::Y,
@@ -337,8 +590,8 @@ pub(crate) const fn plane_tangent(direction: GizmoDirection) -> DVec3 {
 }
 
 pub(crate) fn plane_size(config: &PreparedGizmoConfig) -> f64 {
-    (config.scale_factor * (config.visuals.gizmo_size * 0.1 + config.visuals.stroke_width * 2.0))
-        as f64
+    (config.scale_factor * config.visuals.gizmo_size * 0.1
+        + config.pixel_scale_factor * config.visuals.stroke_width * 2.0) as f64
 }
 
 pub(crate) fn plane_local_origin(config: &PreparedGizmoConfig, direction: GizmoDirection) -> DVec3 {
@@ -362,12 +615,15 @@ pub(crate) fn plane_global_origin(
 
 /// Radius to use for inner circle subgizmos
 pub(crate) fn inner_circle_radius(config: &PreparedGizmoConfig) -> f64 {
-    (config.scale_factor * config.visuals.gizmo_size) as f64 * 0.2
+    (config.scale_factor * config.visuals.gizmo_size * config.visuals.inner_circle_radius_factor)
+        as f64
 }
 
 /// Radius to use for outer circle subgizmos
 pub(crate) fn outer_circle_radius(config: &PreparedGizmoConfig) -> f64 {
-    (config.scale_factor * (config.visuals.gizmo_size + config.visuals.stroke_width + 5.0)) as f64
+    (config.scale_factor * config.visuals.gizmo_size
+        + config.pixel_scale_factor
+            * (config.visuals.stroke_width + config.visuals.outer_circle_margin)) as f64
 }
 
 pub(crate) fn gizmo_local_normal(config: &PreparedGizmoConfig, direction: GizmoDirection) -> DVec3 {
@@ -389,22 +645,18 @@ pub(crate) fn gizmo_normal(config: &PreparedGizmoConfig, direction: GizmoDirecti
     normal
 }
 
+#[cfg(feature = "draw")]
 pub(crate) fn gizmo_color(
     config: &PreparedGizmoConfig,
     focused: bool,
     direction: GizmoDirection,
 ) -> Color32 {
-    let color = match direction {
-        GizmoDirection::X => config.visuals.x_color,
-        GizmoDirection::Y => config.visuals.y_color,
-        GizmoDirection::Z => config.visuals.z_color,
-        GizmoDirection::View => config.visuals.s_color,
-    };
+    let color = config.visuals.axis_color(direction);
 
-    let color = if focused {
-        config.visuals.highlight_color.unwrap_or(color)
-    } else {
-        color
+    let color = match config.visuals.highlight_mode {
+        _ if !focused => color,
+        HighlightMode::ReplaceColor => config.visuals.highlight_color.unwrap_or(color),
+        HighlightMode::BrightenAxisColor => brighten(color),
     };
 
     let alpha = if focused {
@@ -413,5 +665,22 @@ pub(crate) fn gizmo_color(
         config.visuals.inactive_alpha
     };
 
-    color.linear_multiply(alpha)
+    color.linear_multiply(alpha * config.distance_fade())
+}
+
+/// Moves the color a bit towards white, keeping its alpha.
+#[cfg(feature = "draw")]
+fn brighten(color: Color32) -> Color32 {
+    const AMOUNT: f32 = 0.4;
+
+    // The color is premultiplied, so white is at the alpha value.
+    let [r, g, b, a] = color.to_array();
+    let brighten_channel = |c: u8| c + (a.saturating_sub(c) as f32 * AMOUNT) as u8;
+
+    Color32::from_rgba_premultiplied(
+        brighten_channel(r),
+        brighten_channel(g),
+        brighten_channel(b),
+        a,
+    )
 }
@@ -1,15 +1,31 @@
 use std::f64::consts::{FRAC_PI_2, PI, TAU};
 
+#[cfg(feature = "draw")]
 use ecolor::Color32;
 
-use crate::math::{
-    ray_to_plane_origin, rotation_align, round_to_interval, world_to_screen, DMat3, DMat4, DQuat,
-    DVec2, DVec3, Pos2,
-};
+#[cfg(feature = "draw")]
+use crate::config::PreparedGizmoConfig;
+use crate::config::{RotationInputMode, SnapFunctions};
+use crate::math::{ray_to_plane_origin, world_to_screen, DVec2, DVec3, Pos2, Vec2};
+#[cfg(feature = "draw")]
+use crate::math::{rotation_align, DMat3, DMat4, DQuat, EulerRot};
+#[cfg(feature = "draw")]
 use crate::shape::ShapeBuidler;
-use crate::subgizmo::common::{gizmo_color, gizmo_local_normal, gizmo_normal, outer_circle_radius};
-use crate::subgizmo::{SubGizmoConfig, SubGizmoKind};
-use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoResult};
+#[cfg(feature = "draw")]
+use crate::subgizmo::common::gizmo_color;
+use crate::subgizmo::common::{gizmo_local_normal, gizmo_normal, outer_circle_radius};
+use crate::subgizmo::{common::TransformKind, SubGizmoConfig, SubGizmoKind};
+#[cfg(feature = "draw")]
+use crate::GizmoDrawData;
+use crate::{gizmo::Ray, GizmoDirection, GizmoMode, GizmoOrientation, GizmoResult};
+
+/// Maximum number of snapping ticks drawn around the rotation axis.
+#[cfg(feature = "draw")]
+const MAX_SNAP_TICKS: usize = 360;
+
+/// Opacity of the leading edge of rotation arcs drawn with a gradient.
+#[cfg(feature = "draw")]
+const ARC_GRADIENT_FADE: f32 = 0.2;
 
 pub(crate) type RotationSubGizmo = SubGizmoConfig<Rotation>;
 
@@ -23,7 +39,12 @@ pub(crate) struct RotationState {
     start_axis_angle: f64,
     start_rotation_angle: f64,
     last_rotation_angle: f64,
+    last_raw_rotation_angle: f64,
+    last_input_angle: f64,
+    last_cursor_pos: Pos2,
+    screen_tangent: DVec2,
     current_delta: f64,
+    current_raw_delta: f64,
 }
 
 #[derive(Default, Debug, Copy, Clone)]
@@ -33,6 +54,16 @@ impl SubGizmoKind for Rotation {
     type Params = RotationParams;
     type State = RotationState;
 
+    const MODE: GizmoMode = GizmoMode::Rotate;
+
+    fn direction(subgizmo: &RotationSubGizmo) -> GizmoDirection {
+        subgizmo.direction
+    }
+
+    fn transform_kind(_subgizmo: &RotationSubGizmo) -> TransformKind {
+        TransformKind::Axis
+    }
+
     fn pick(subgizmo: &mut RotationSubGizmo, ray: Ray) -> Option<f64> {
         let radius = arc_radius(subgizmo);
         let config = subgizmo.config;
@@ -64,7 +95,13 @@ impl SubGizmoKind for Rotation {
         subgizmo.state.start_axis_angle = angle;
         subgizmo.state.start_rotation_angle = rotation_angle;
         subgizmo.state.last_rotation_angle = rotation_angle;
+        subgizmo.state.last_raw_rotation_angle = rotation_angle;
+        subgizmo.state.last_input_angle = rotation_angle;
+        subgizmo.state.last_cursor_pos = ray.screen_pos;
+        subgizmo.state.screen_tangent =
+            screen_tangent(subgizmo, nearest_circle_pos).unwrap_or_default();
         subgizmo.state.current_delta = 0.0;
+        subgizmo.state.current_raw_delta = 0.0;
 
         if dist_from_gizmo_edge <= config.focus_distance as f64 && angle.abs() < arc_angle(subgizmo)
         {
@@ -74,28 +111,46 @@ impl SubGizmoKind for Rotation {
         }
     }
 
-    fn update(subgizmo: &mut RotationSubGizmo, ray: Ray) -> Option<GizmoResult> {
+    fn update(
+        subgizmo: &mut RotationSubGizmo,
+        ray: Ray,
+        snap_fns: &SnapFunctions,
+    ) -> Option<GizmoResult> {
         let config = subgizmo.config;
 
-        let mut rotation_angle = rotation_angle(subgizmo, ray.screen_pos)?;
-        if config.snapping {
-            rotation_angle = round_to_interval(
+        let input_delta = match config.rotation_mode {
+            RotationInputMode::Angle => {
+                let input_angle = rotation_angle(subgizmo, ray.screen_pos)?;
+                let input_delta = shortest_angle(input_angle - subgizmo.state.last_input_angle);
+                subgizmo.state.last_input_angle = input_angle;
+                input_delta
+            }
+            RotationInputMode::Tangent => {
+                let cursor_delta = ray.screen_pos - subgizmo.state.last_cursor_pos;
+                subgizmo.state.last_cursor_pos = ray.screen_pos;
+                tangent_angle_delta(subgizmo, cursor_delta)
+            }
+        };
+
+        let raw_rotation_angle =
+            subgizmo.state.last_raw_rotation_angle + input_delta * config.motion_scale();
+        let mut rotation_angle = raw_rotation_angle;
+        if config.is_snapping() {
+            rotation_angle = snap_fns.snap(
+                GizmoMode::Rotate,
                 rotation_angle - subgizmo.state.start_rotation_angle,
                 config.snap_angle as f64,
             ) + subgizmo.state.start_rotation_angle;
         }
 
-        let mut angle_delta = rotation_angle - subgizmo.state.last_rotation_angle;
-
-        // Always take the smallest angle, e.g. -10° instead of 350°
-        if angle_delta > PI {
-            angle_delta -= TAU;
-        } else if angle_delta < -PI {
-            angle_delta += TAU;
-        }
+        let angle_delta = shortest_angle(rotation_angle - subgizmo.state.last_rotation_angle);
+        let raw_angle_delta =
+            shortest_angle(raw_rotation_angle - subgizmo.state.last_raw_rotation_angle);
 
         subgizmo.state.last_rotation_angle = rotation_angle;
+        subgizmo.state.last_raw_rotation_angle = raw_rotation_angle;
         subgizmo.state.current_delta += angle_delta;
+        subgizmo.state.current_raw_delta += raw_angle_delta;
 
         let normal = gizmo_local_normal(&subgizmo.config, subgizmo.direction);
 
@@ -103,10 +158,19 @@ impl SubGizmoKind for Rotation {
             axis: normal.into(),
             delta: -angle_delta,
             total: subgizmo.state.current_delta,
+            raw_total: subgizmo.state.current_raw_delta,
+            snapped: rotation_angle != raw_rotation_angle,
             is_view_axis: subgizmo.direction == GizmoDirection::View,
+            direction: subgizmo.direction,
+            space: if subgizmo.direction == GizmoDirection::View {
+                GizmoOrientation::Global
+            } else {
+                config.orientation()
+            },
         })
     }
 
+    #[cfg(feature = "draw")]
     fn draw(subgizmo: &RotationSubGizmo) -> GizmoDrawData {
         let config = subgizmo.config;
 
@@ -115,6 +179,7 @@ impl SubGizmoKind for Rotation {
             config.view_projection * transform,
             config.viewport,
             config.pixels_per_point,
+            config.visuals.circle_segments,
         );
 
         let color = gizmo_color(&subgizmo.config, subgizmo.focused, subgizmo.direction);
@@ -126,9 +191,24 @@ impl SubGizmoKind for Rotation {
 
         if !subgizmo.active {
             let angle = arc_angle(subgizmo);
-            draw_data += shape_builder
-                .arc(radius, FRAC_PI_2 - angle, FRAC_PI_2 + angle, stroke)
-                .into();
+            if config.visuals.rotation_arc_gradient {
+                // Angles on the arc grow in the direction of negative rotation
+                // around the axis, so the arc fades out towards its start.
+                draw_data += shape_builder
+                    .gradient_arc(
+                        radius,
+                        FRAC_PI_2 - angle,
+                        FRAC_PI_2 + angle,
+                        stroke.0,
+                        color.linear_multiply(ARC_GRADIENT_FADE),
+                        color,
+                    )
+                    .into();
+            } else {
+                draw_data += shape_builder
+                    .arc(radius, FRAC_PI_2 - angle, FRAC_PI_2 + angle, stroke)
+                    .into();
+            }
         } else {
             let mut start_angle = subgizmo.state.start_axis_angle + FRAC_PI_2;
             let mut end_angle = start_angle + subgizmo.state.current_delta;
@@ -198,10 +278,15 @@ impl SubGizmoKind for Rotation {
             draw_data += shape_builder.circle(radius, stroke).into();
 
             // Draw snapping ticks
-            if config.snapping {
+            if config.is_snapping() {
                 let stroke_width = stroke.0 / 2.0;
-                for i in 0..((TAU / config.snap_angle as f64) as usize + 1) {
-                    let angle = i as f64 * config.snap_angle as f64 + end_angle;
+                let snap_angle = config.snap_angle as f64;
+                let snap_count = (TAU / snap_angle).ceil() as usize;
+                // Draw only every nth tick if there would be too many of them.
+                let tick_stride = snap_count.div_ceil(MAX_SNAP_TICKS).max(1);
+                let tick_angle = snap_angle * tick_stride as f64;
+                for i in 0..=(snap_count / tick_stride) {
+                    let angle = i as f64 * tick_angle + end_angle;
                     let pos = DVec3::new(angle.cos(), 0.0, angle.sin());
                     draw_data += shape_builder
                         .line_segment(
@@ -218,6 +303,51 @@ impl SubGizmoKind for Rotation {
     }
 }
 
+/// Draws three nested rings, one for each axis of the yaw-pitch-roll
+/// decomposition of the gizmo rotation. When pitch approaches ±90°,
+/// the yaw and roll rings become coplanar, i.e. the gimbal locks.
+#[cfg(feature = "draw")]
+pub(crate) fn draw_gimbal_rings(config: &PreparedGizmoConfig) -> GizmoDrawData {
+    let (yaw, pitch, _) = config.rotation.to_euler(EulerRot::YXZ);
+    let yaw_frame = DQuat::from_rotation_y(yaw);
+    let pitch_frame = yaw_frame * DQuat::from_rotation_x(pitch);
+
+    // The rings are drawn on the XZ plane, so each of them
+    // is first rotated so that Y points along its axis.
+    let rings = [
+        (DQuat::IDENTITY, GizmoDirection::Y, 0.85),
+        (
+            yaw_frame * DQuat::from_rotation_arc(DVec3::Y, DVec3::X),
+            GizmoDirection::X,
+            0.7,
+        ),
+        (
+            pitch_frame * DQuat::from_rotation_arc(DVec3::Y, DVec3::Z),
+            GizmoDirection::Z,
+            0.55,
+        ),
+    ];
+
+    let mut draw_data = GizmoDrawData::default();
+    for (rotation, direction, radius_factor) in rings {
+        let shape_builder = ShapeBuidler::new(
+            config.view_projection * DMat4::from_rotation_translation(rotation, config.translation),
+            config.viewport,
+            config.pixels_per_point,
+            config.visuals.circle_segments,
+        );
+
+        let radius = (config.scale_factor * config.visuals.gizmo_size) as f64 * radius_factor;
+        let color = gizmo_color(config, false, direction);
+
+        draw_data += shape_builder
+            .circle(radius, (config.visuals.stroke_width / 2.0, color))
+            .into();
+    }
+
+    draw_data
+}
+
 /// Calculates angle of the rotation axis arc.
 /// The arc is a semicircle, which turns into a full circle when viewed
 /// directly from the front.
@@ -237,6 +367,7 @@ fn arc_angle(subgizmo: &SubGizmoConfig<Rotation>) -> f64 {
 }
 
 /// Calculates a matrix used when rendering the rotation axis.
+#[cfg(feature = "draw")]
 fn rotation_matrix(subgizmo: &SubGizmoConfig<Rotation>) -> DMat4 {
     if subgizmo.direction == GizmoDirection::View {
         let forward = subgizmo.config.view_forward();
@@ -298,6 +429,56 @@ fn rotation_angle(subgizmo: &SubGizmoConfig<Rotation>, cursor_pos: Pos2) -> Opti
     Some(angle)
 }
 
+/// Screen space direction of the ring at the given world space point on it,
+/// oriented so that moving the cursor along it grows [`rotation_angle`].
+fn screen_tangent(subgizmo: &SubGizmoConfig<Rotation>, point: DVec3) -> Option<DVec2> {
+    let config = &subgizmo.config;
+    let to_screen = |point: DVec3| {
+        world_to_screen(config.viewport, config.view_projection, point)
+            .map(|pos| DVec2::new(pos.x as f64, pos.y as f64))
+    };
+
+    let normal = gizmo_normal(config, subgizmo.direction);
+    let ring_tangent = normal.cross(point - config.translation);
+
+    let center = to_screen(config.translation)?;
+    let from = to_screen(point)?;
+    let to = to_screen(point + ring_tangent * 1e-2)?;
+
+    // The angle from the center to the cursor grows along the perpendicular of the radius.
+    let mut tangent = (to - from).normalize_or_zero();
+    if tangent.dot((from - center).perp()) < 0.0 {
+        tangent = -tangent;
+    }
+    if config.view_forward().dot(normal) < 0.0 {
+        tangent = -tangent;
+    }
+
+    Some(tangent)
+}
+
+/// Change of [`rotation_angle`] for a cursor movement along the ring in
+/// [`RotationInputMode::Tangent`], moving by the radius of the ring per radian.
+fn tangent_angle_delta(subgizmo: &SubGizmoConfig<Rotation>, cursor_delta: Vec2) -> f64 {
+    let radius = arc_radius(subgizmo) / subgizmo.config.pixel_size as f64;
+    if !radius.is_normal() {
+        return 0.0;
+    }
+
+    DVec2::new(cursor_delta.x as f64, cursor_delta.y as f64).dot(subgizmo.state.screen_tangent)
+        / radius
+}
+
+/// Always take the smallest angle, e.g. -10° instead of 350°
+fn shortest_angle(mut angle: f64) -> f64 {
+    if angle > PI {
+        angle -= TAU;
+    } else if angle < -PI {
+        angle += TAU;
+    }
+    angle
+}
+
 fn tangent(subgizmo: &SubGizmoConfig<Rotation>) -> DVec3 {
     let mut tangent = match subgizmo.direction {
         GizmoDirection::X | GizmoDirection::Y => DVec3::Z,
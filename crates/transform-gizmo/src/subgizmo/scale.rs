@@ -1,13 +1,21 @@
 use glam::DVec3;
 
-use crate::math::{round_to_interval, world_to_screen, Pos2};
+use crate::config::SnapFunctions;
+use crate::math::{world_to_screen, DVec2, Pos2};
 
+#[cfg(feature = "draw")]
+use crate::subgizmo::common::{draw_arrow, draw_circle, draw_plane, gizmo_color};
 use crate::subgizmo::common::{
-    draw_arrow, draw_circle, draw_plane, gizmo_color, gizmo_local_normal, inner_circle_radius,
-    outer_circle_radius, pick_arrow, pick_circle, pick_plane, plane_bitangent, plane_tangent,
+    gizmo_local_normal, inner_circle_radius, outer_circle_radius, pick_arrow, pick_circle,
+    pick_plane, plane_bitangent, plane_tangent,
 };
 use crate::subgizmo::{common::TransformKind, SubGizmoConfig, SubGizmoKind};
-use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoMode, GizmoResult};
+#[cfg(feature = "draw")]
+use crate::GizmoDrawData;
+use crate::{gizmo::Ray, GizmoDirection, GizmoMode, GizmoResult};
+
+/// Smallest magnitude of the scale factor, keeping the scale away from zero.
+const MIN_SCALE_FACTOR: f64 = 1e-4;
 
 pub(crate) type ScaleSubGizmo = SubGizmoConfig<Scale>;
 
@@ -20,6 +28,11 @@ pub(crate) struct ScaleParams {
 #[derive(Default, Debug, Copy, Clone)]
 pub(crate) struct ScaleState {
     start_delta: f64,
+    start_offset: DVec2,
+    start_scale: DVec3,
+    last_scale: DVec3,
+    last_raw_delta: f64,
+    last_input_delta: f64,
 }
 
 #[derive(Default, Debug, Copy, Clone)]
@@ -29,6 +42,16 @@ impl SubGizmoKind for Scale {
     type Params = ScaleParams;
     type State = ScaleState;
 
+    const MODE: GizmoMode = GizmoMode::Scale;
+
+    fn direction(subgizmo: &ScaleSubGizmo) -> GizmoDirection {
+        subgizmo.direction
+    }
+
+    fn transform_kind(subgizmo: &ScaleSubGizmo) -> TransformKind {
+        subgizmo.transform_kind
+    }
+
     fn pick(subgizmo: &mut ScaleSubGizmo, ray: Ray) -> Option<f64> {
         let pick_result = match (subgizmo.transform_kind, subgizmo.direction) {
             (TransformKind::Plane, GizmoDirection::View) => {
@@ -54,11 +77,16 @@ impl SubGizmoKind for Scale {
             }
         };
 
-        let start_delta = distance_from_origin_2d(subgizmo, ray.screen_pos)?;
+        let start_offset = offset_from_origin_2d(subgizmo, ray.screen_pos)?;
 
         subgizmo.opacity = pick_result.visibility as _;
 
-        subgizmo.state.start_delta = start_delta;
+        subgizmo.state.start_delta = start_offset.length();
+        subgizmo.state.start_offset = start_offset;
+        subgizmo.state.start_scale = subgizmo.config.scale;
+        subgizmo.state.last_scale = DVec3::ONE;
+        subgizmo.state.last_raw_delta = 1.0;
+        subgizmo.state.last_input_delta = 1.0;
 
         if pick_result.picked {
             Some(pick_result.t)
@@ -67,14 +95,29 @@ impl SubGizmoKind for Scale {
         }
     }
 
-    fn update(subgizmo: &mut ScaleSubGizmo, ray: Ray) -> Option<GizmoResult> {
-        let mut delta = distance_from_origin_2d(subgizmo, ray.screen_pos)?;
-        delta /= subgizmo.state.start_delta;
+    fn update(
+        subgizmo: &mut ScaleSubGizmo,
+        ray: Ray,
+        snap_fns: &SnapFunctions,
+    ) -> Option<GizmoResult> {
+        let offset = offset_from_origin_2d(subgizmo, ray.screen_pos)?;
+        let input_delta = if subgizmo.config.allow_negative_scale {
+            // Moving the cursor past the center of the gizmo turns the scale negative.
+            offset.dot(subgizmo.state.start_offset) / subgizmo.state.start_delta.powi(2)
+        } else {
+            offset.length() / subgizmo.state.start_delta
+        };
+        let input_delta = 1.0 + (input_delta - 1.0) * subgizmo.config.scale_sensitivity as f64;
+
+        let raw_delta = subgizmo.state.last_raw_delta
+            + (input_delta - subgizmo.state.last_input_delta) * subgizmo.config.motion_scale();
+        subgizmo.state.last_input_delta = input_delta;
+        subgizmo.state.last_raw_delta = raw_delta;
 
-        if subgizmo.config.snapping {
-            delta = round_to_interval(delta, subgizmo.config.snap_scale as f64);
+        let mut delta = raw_delta;
+        if subgizmo.config.is_snapping() {
+            delta = snap_fns.snap(GizmoMode::Scale, delta, subgizmo.config.snap_scale as f64);
         }
-        delta = delta.max(1e-4) - 1.0;
 
         let direction = match (subgizmo.transform_kind, subgizmo.direction) {
             (TransformKind::Axis, _) => gizmo_local_normal(&subgizmo.config, subgizmo.direction),
@@ -84,13 +127,49 @@ impl SubGizmoKind for Scale {
             .normalize(),
         };
 
-        let scale = DVec3::ONE + (direction * delta);
+        // Snap the resulting scale to the closest scale target, if any.
+        let reference_scale = match (subgizmo.transform_kind, subgizmo.direction) {
+            (TransformKind::Axis, _) => Some(subgizmo.state.start_scale.dot(direction.abs())),
+            (TransformKind::Plane, GizmoDirection::View) => {
+                Some(subgizmo.state.start_scale.max_element())
+            }
+            (TransformKind::Plane, _) => None,
+        };
+        if let Some(reference_scale) = reference_scale.filter(|scale| *scale > 1e-10) {
+            if let Some(target) = snap_fns.snap_scale_to_target(reference_scale * delta) {
+                delta = target / reference_scale;
+            }
+        }
+
+        let allow_negative = subgizmo.config.allow_negative_scale;
+        let mut scale = DVec3::ONE + (direction * (avoid_zero(delta, allow_negative) - 1.0));
+        if let Some((min, max)) = subgizmo.config.scale_clamp {
+            scale = clamp_scale(subgizmo.state.start_scale, scale, direction, min, max);
+        }
+        let raw_scale = DVec3::ONE + (direction * (avoid_zero(raw_delta, allow_negative) - 1.0));
+
+        // A component may pass through zero when negative scales are allowed,
+        // in which case there is no meaningful relative change for that frame.
+        let last_scale = subgizmo.state.last_scale;
+        let delta_scale = DVec3::select(
+            last_scale.abs().cmpgt(DVec3::splat(1e-10)),
+            scale / last_scale,
+            DVec3::ONE,
+        );
+        subgizmo.state.last_scale = scale;
 
         Some(GizmoResult::Scale {
+            delta: delta_scale.into(),
             total: scale.into(),
+            raw_total: raw_scale.into(),
+            snapped: delta != raw_delta,
+            direction: subgizmo.direction,
+            transform_kind: subgizmo.transform_kind,
+            space: subgizmo.config.orientation(),
         })
     }
 
+    #[cfg(feature = "draw")]
     fn draw(subgizmo: &ScaleSubGizmo) -> GizmoDrawData {
         match (subgizmo.transform_kind, subgizmo.direction) {
             (TransformKind::Axis, _) => draw_arrow(
@@ -123,12 +202,47 @@ impl SubGizmoKind for Scale {
     }
 }
 
-fn distance_from_origin_2d<T: SubGizmoKind>(
+/// Clamps the magnitudes of the components of `start_scale * scale` to the given
+/// range, keeping their signs. Only the components along `direction` are clamped,
+/// so that the other axes are unaffected.
+pub(crate) fn clamp_scale(
+    start_scale: DVec3,
+    scale: DVec3,
+    direction: DVec3,
+    min: f32,
+    max: f32,
+) -> DVec3 {
+    let mask = direction.cmpne(DVec3::ZERO) & start_scale.abs().cmpgt(DVec3::splat(1e-10));
+    let total = start_scale * scale;
+    let clamped = total
+        .abs()
+        .max(DVec3::splat(min as f64))
+        .min(DVec3::splat(max as f64))
+        * total.signum()
+        / start_scale;
+
+    DVec3::select(mask, clamped, scale)
+}
+
+/// Keeps the scale factor away from zero. Negative factors are kept negative if
+/// they are allowed, and are otherwise raised to the smallest positive factor.
+fn avoid_zero(factor: f64, allow_negative: bool) -> f64 {
+    if allow_negative && factor < 0.0 {
+        factor.min(-MIN_SCALE_FACTOR)
+    } else {
+        factor.max(MIN_SCALE_FACTOR)
+    }
+}
+
+fn offset_from_origin_2d<T: SubGizmoKind>(
     subgizmo: &SubGizmoConfig<T>,
     cursor_pos: Pos2,
-) -> Option<f64> {
+) -> Option<DVec2> {
     let viewport = subgizmo.config.viewport;
     let gizmo_pos = world_to_screen(viewport, subgizmo.config.mvp, DVec3::new(0.0, 0.0, 0.0))?;
 
-    Some(cursor_pos.distance(gizmo_pos) as f64)
+    Some(DVec2::new(
+        (cursor_pos.x - gizmo_pos.x) as f64,
+        (cursor_pos.y - gizmo_pos.y) as f64,
+    ))
 }
@@ -1,11 +1,28 @@
-use crate::math::{intersect_plane, ray_to_ray, round_to_interval, DVec3};
+use crate::config::{PreparedGizmoConfig, SnapFunctions};
+use crate::math::{intersect_plane, ray_to_ray, world_to_screen, DVec3, Pos2};
 
+#[cfg(feature = "draw")]
+use crate::subgizmo::common::{draw_arrow, draw_circle, draw_plane, gizmo_color};
 use crate::subgizmo::common::{
-    draw_arrow, draw_circle, draw_plane, gizmo_color, gizmo_normal, inner_circle_radius,
-    pick_arrow, pick_circle, pick_plane, plane_bitangent, plane_global_origin, plane_tangent,
+    gizmo_normal, inner_circle_radius, pick_arrow, pick_circle, pick_plane, plane_bitangent,
+    plane_global_origin, plane_tangent, PickResult,
 };
 use crate::subgizmo::{common::TransformKind, SubGizmoConfig, SubGizmoKind};
-use crate::{gizmo::Ray, GizmoDirection, GizmoDrawData, GizmoMode, GizmoOrientation, GizmoResult};
+use crate::{gizmo::Ray, GizmoDirection, GizmoMode, GizmoOrientation, GizmoResult};
+#[cfg(feature = "draw")]
+use crate::{shape::ShapeBuidler, GizmoDrawData};
+
+/// Distance of the dolly handle below the gizmo center, relative to the gizmo size
+const DOLLY_HANDLE_OFFSET: f64 = 1.35;
+/// Radius of the dolly handle, relative to the stroke width
+const DOLLY_HANDLE_RADIUS: f32 = 1.5;
+
+/// Number of snapping grid cells drawn on each side of the gizmo
+#[cfg(feature = "draw")]
+const SNAP_GRID_CELLS: i32 = 3;
+/// Number of snapping ticks drawn on each side of the gizmo along a translation axis
+#[cfg(feature = "draw")]
+const SNAP_TICKS_PER_SIDE: i32 = 5;
 
 pub(crate) type TranslationSubGizmo = SubGizmoConfig<Translation>;
 
@@ -17,8 +34,23 @@ pub(crate) struct TranslationParams {
 
 #[derive(Default, Debug, Copy, Clone)]
 pub(crate) struct TranslationState {
+    start_origin: DVec3,
+    /// Normal of the drag plane, captured when the subgizmo is picked.
+    plane_normal: DVec3,
+    /// Origin of the drag plane, captured when the subgizmo is picked.
+    plane_origin: DVec3,
+    /// Axis lock that was used for the previous update
+    axis_lock: Option<(GizmoDirection, TransformKind)>,
+    /// Direction away from the camera for dollying, captured when the subgizmo is picked.
+    dolly_axis: DVec3,
+    /// Screen position of the pointer when the subgizmo was picked
+    start_screen_pos: Pos2,
+    /// World space size of a pixel at the gizmo when the subgizmo was picked
+    start_pixel_size: f64,
     start_point: DVec3,
     last_point: DVec3,
+    last_raw_point: DVec3,
+    last_input_point: DVec3,
     current_delta: DVec3,
 }
 
@@ -29,8 +61,19 @@ impl SubGizmoKind for Translation {
     type Params = TranslationParams;
     type State = TranslationState;
 
+    const MODE: GizmoMode = GizmoMode::Translate;
+
+    fn direction(subgizmo: &TranslationSubGizmo) -> GizmoDirection {
+        subgizmo.direction
+    }
+
+    fn transform_kind(subgizmo: &TranslationSubGizmo) -> TransformKind {
+        subgizmo.transform_kind
+    }
+
     fn pick(subgizmo: &mut TranslationSubGizmo, ray: Ray) -> Option<f64> {
         let pick_result = match (subgizmo.transform_kind, subgizmo.direction) {
+            (TransformKind::Axis, GizmoDirection::View) => pick_dolly_handle(&subgizmo.config, ray),
             (TransformKind::Plane, GizmoDirection::View) => pick_circle(
                 &subgizmo.config,
                 ray,
@@ -48,8 +91,19 @@ impl SubGizmoKind for Translation {
 
         subgizmo.opacity = pick_result.visibility as _;
 
+        subgizmo.state.start_origin = subgizmo.config.translation;
+        // Keep dragging on the plane as it was at grab time,
+        // even if the camera moves during the drag.
+        subgizmo.state.plane_normal = gizmo_normal(&subgizmo.config, subgizmo.direction);
+        subgizmo.state.plane_origin = plane_global_origin(&subgizmo.config, subgizmo.direction);
+        subgizmo.state.axis_lock = None;
+        subgizmo.state.dolly_axis = view_axis_away_from_camera(&subgizmo.config, ray);
+        subgizmo.state.start_screen_pos = ray.screen_pos;
+        subgizmo.state.start_pixel_size = subgizmo.config.pixel_size as f64;
         subgizmo.state.start_point = pick_result.subgizmo_point;
         subgizmo.state.last_point = pick_result.subgizmo_point;
+        subgizmo.state.last_raw_point = pick_result.subgizmo_point;
+        subgizmo.state.last_input_point = pick_result.subgizmo_point;
         subgizmo.state.current_delta = DVec3::ZERO;
 
         if pick_result.picked {
@@ -59,35 +113,86 @@ impl SubGizmoKind for Translation {
         }
     }
 
-    fn update(subgizmo: &mut TranslationSubGizmo, ray: Ray) -> Option<GizmoResult> {
-        let mut new_point = if subgizmo.transform_kind == TransformKind::Axis {
-            point_on_axis(subgizmo, ray)
-        } else {
-            point_on_plane(
-                gizmo_normal(&subgizmo.config, subgizmo.direction),
-                plane_global_origin(&subgizmo.config, subgizmo.direction),
+    fn update(
+        subgizmo: &mut TranslationSubGizmo,
+        ray: Ray,
+        snap_fns: &SnapFunctions,
+    ) -> Option<GizmoResult> {
+        let axis_lock = axis_lock(subgizmo);
+        let (direction, transform_kind) =
+            axis_lock.unwrap_or((subgizmo.direction, subgizmo.transform_kind));
+
+        let input_point = match (transform_kind, axis_lock) {
+            (TransformKind::Axis, _) if direction == GizmoDirection::View => {
+                point_on_dolly_axis(subgizmo, ray)
+            }
+            (TransformKind::Axis, _) => point_on_axis(subgizmo, direction, ray),
+            (TransformKind::Plane, None) => point_on_plane(
+                subgizmo.state.plane_normal,
+                subgizmo.state.plane_origin,
                 ray,
-            )?
+            )?,
+            (TransformKind::Plane, Some(_)) => point_on_plane(
+                gizmo_normal(&subgizmo.config, direction),
+                subgizmo.state.start_origin,
+                ray,
+            )?,
         };
 
+        if axis_lock != subgizmo.state.axis_lock {
+            // Project the translation so far onto the new constraint,
+            // and continue from the current pointer position.
+            subgizmo.state.axis_lock = axis_lock;
+            subgizmo.state.last_input_point = input_point;
+            subgizmo.state.last_raw_point = subgizmo.state.start_point
+                + constrain(
+                    subgizmo,
+                    subgizmo.state.last_raw_point - subgizmo.state.start_point,
+                );
+        }
+
+        let raw_point = subgizmo.state.last_raw_point
+            + (input_point - subgizmo.state.last_input_point) * subgizmo.config.motion_scale();
+        subgizmo.state.last_input_point = input_point;
+        subgizmo.state.last_raw_point = raw_point;
+
+        let mut new_point = raw_point;
         let mut new_delta = new_point - subgizmo.state.start_point;
 
-        if subgizmo.config.snapping {
-            new_delta = if subgizmo.transform_kind == TransformKind::Axis {
-                snap_translation_vector(subgizmo, new_delta)
+        if subgizmo.config.is_snapping() {
+            new_delta = if transform_kind == TransformKind::Axis {
+                snap_translation_vector(subgizmo, snap_fns, direction, new_delta)
             } else {
-                snap_translation_plane(subgizmo, new_delta)
+                snap_translation_plane(subgizmo, snap_fns, direction, new_delta)
             };
             new_point = subgizmo.state.start_point + new_delta;
         }
 
+        let snapped = new_point != raw_point;
+
+        // Keep the gizmo within the bounds. Each axis is clamped separately,
+        // so that the gizmo can still slide along the boundary.
+        let mut clamped = false;
+        if let Some((min, max)) = subgizmo.config.translation_bounds {
+            let new_origin = subgizmo.state.start_origin + new_delta;
+            let clamped_origin = new_origin.max(min.into()).min(max.into());
+
+            if clamped_origin != new_origin {
+                clamped = true;
+                new_delta = clamped_origin - subgizmo.state.start_origin;
+                new_point = subgizmo.state.start_point + new_delta;
+            }
+        }
+
         let mut translation_delta = new_point - subgizmo.state.last_point;
         let mut total_translation = new_point - subgizmo.state.start_point;
+        let mut raw_total_translation = raw_point - subgizmo.state.start_point;
 
         if subgizmo.config.orientation() == GizmoOrientation::Local {
             let inverse_rotation = subgizmo.config.rotation.inverse();
             translation_delta = inverse_rotation * translation_delta;
             total_translation = inverse_rotation * total_translation;
+            raw_total_translation = inverse_rotation * raw_total_translation;
         }
 
         subgizmo.state.last_point = new_point;
@@ -96,38 +201,264 @@ impl SubGizmoKind for Translation {
         Some(GizmoResult::Translation {
             delta: translation_delta.into(),
             total: total_translation.into(),
+            raw_total: raw_total_translation.into(),
+            snapped,
+            clamped,
+            // Report the axis or plane that the translation is locked to, if any.
+            direction,
+            transform_kind,
+            space: subgizmo.config.orientation(),
         })
     }
 
+    #[cfg(feature = "draw")]
     fn draw(subgizmo: &TranslationSubGizmo) -> GizmoDrawData {
         match (subgizmo.transform_kind, subgizmo.direction) {
-            (TransformKind::Axis, _) => draw_arrow(
-                &subgizmo.config,
-                subgizmo.opacity,
-                subgizmo.focused,
-                subgizmo.direction,
-                GizmoMode::Translate,
-            ),
+            (TransformKind::Axis, GizmoDirection::View) => draw_dolly_handle(subgizmo),
+            (TransformKind::Axis, _) => {
+                let mut draw_data = draw_arrow(
+                    &subgizmo.config,
+                    subgizmo.opacity,
+                    subgizmo.focused,
+                    subgizmo.direction,
+                    GizmoMode::Translate,
+                );
+
+                if subgizmo.active && subgizmo.config.is_snapping() {
+                    draw_data += draw_snap_ticks(subgizmo);
+                }
+
+                draw_data
+            }
             (TransformKind::Plane, GizmoDirection::View) => draw_circle(
                 &subgizmo.config,
                 gizmo_color(&subgizmo.config, subgizmo.focused, subgizmo.direction),
                 inner_circle_radius(&subgizmo.config),
                 false,
             ),
-            (TransformKind::Plane, _) => draw_plane(
-                &subgizmo.config,
-                subgizmo.opacity,
-                subgizmo.focused,
-                subgizmo.direction,
-            ),
+            (TransformKind::Plane, _) => {
+                let mut draw_data = draw_plane(
+                    &subgizmo.config,
+                    subgizmo.opacity,
+                    subgizmo.focused,
+                    subgizmo.direction,
+                );
+
+                if subgizmo.active
+                    && subgizmo.config.is_snapping()
+                    && subgizmo.config.visuals.snap_grid
+                {
+                    draw_data += draw_snap_grid(subgizmo);
+                }
+
+                draw_data
+            }
+        }
+    }
+}
+
+/// Draws tick marks across the translation axis around the gizmo,
+/// at the points where the gizmo snaps to.
+#[cfg(feature = "draw")]
+fn draw_snap_ticks(subgizmo: &SubGizmoConfig<Translation>) -> GizmoDrawData {
+    let config = &subgizmo.config;
+
+    let axis = gizmo_normal(config, subgizmo.direction);
+    let across = axis.cross(config.view_forward());
+    if across.length_squared() < 1e-10 {
+        // The axis points towards the camera, so the ticks would overlap.
+        return GizmoDrawData::default();
+    }
+    let tick =
+        across.normalize() * (config.pixel_scale_factor * config.visuals.stroke_width) as f64;
+
+    let shape_builder = ShapeBuidler::new(
+        config.view_projection,
+        config.viewport,
+        config.pixels_per_point,
+        config.visuals.circle_segments,
+    );
+
+    let stroke = (
+        config.visuals.stroke_width / 2.0,
+        gizmo_color(config, true, subgizmo.direction),
+    );
+
+    let spacing = config.snap_distance_along(local_axis(subgizmo.direction));
+    if !spacing.is_normal() {
+        return GizmoDrawData::default();
+    }
+
+    // The snapped delta always lands on a tick, so the ticks are centered on it.
+    let current = (subgizmo.state.current_delta.dot(axis) / spacing).round();
+
+    let mut draw_data = GizmoDrawData::default();
+    for i in -SNAP_TICKS_PER_SIDE..=SNAP_TICKS_PER_SIDE {
+        let center = subgizmo.state.start_origin + axis * (spacing * (current + i as f64));
+
+        draw_data += shape_builder
+            .line_segment(center - tick, center + tick, stroke)
+            .into();
+    }
+
+    draw_data
+}
+
+/// Draws a patch of grid on the translation plane around the gizmo.
+/// The grid lines are placed at the points where the gizmo snaps to.
+#[cfg(feature = "draw")]
+fn draw_snap_grid(subgizmo: &SubGizmoConfig<Translation>) -> GizmoDrawData {
+    let config = &subgizmo.config;
+
+    let mut bitangent = plane_bitangent(subgizmo.direction);
+    let mut tangent = plane_tangent(subgizmo.direction);
+    if config.local_space() {
+        bitangent = config.rotation * bitangent;
+        tangent = config.rotation * tangent;
+    }
+
+    let shape_builder = ShapeBuidler::new(
+        config.view_projection,
+        config.viewport,
+        config.pixels_per_point,
+        config.visuals.circle_segments,
+    );
+
+    let stroke = (
+        config.visuals.guide_stroke_width,
+        config.visuals.guide_color,
+    );
+
+    // The snapped delta always lands on the grid, so the grid is centered on it.
+    let center = subgizmo.state.start_origin + subgizmo.state.current_delta;
+    let bitangent_spacing = config.snap_distance_along(plane_bitangent(subgizmo.direction));
+    let tangent_spacing = config.snap_distance_along(plane_tangent(subgizmo.direction));
+    let bitangent_extent = bitangent_spacing * SNAP_GRID_CELLS as f64;
+    let tangent_extent = tangent_spacing * SNAP_GRID_CELLS as f64;
+
+    let mut draw_data = GizmoDrawData::default();
+    for i in -SNAP_GRID_CELLS..=SNAP_GRID_CELLS {
+        let tangent_offset = tangent * (tangent_spacing * i as f64);
+        let bitangent_offset = bitangent * (bitangent_spacing * i as f64);
+
+        draw_data += shape_builder
+            .line_segment(
+                center + tangent_offset - bitangent * bitangent_extent,
+                center + tangent_offset + bitangent * bitangent_extent,
+                stroke,
+            )
+            .into();
+        draw_data += shape_builder
+            .line_segment(
+                center + bitangent_offset - tangent * tangent_extent,
+                center + bitangent_offset + tangent * tangent_extent,
+                stroke,
+            )
+            .into();
+    }
+
+    draw_data
+}
+
+/// World space position of the dolly handle
+fn dolly_handle_position(config: &PreparedGizmoConfig) -> DVec3 {
+    let offset = (config.scale_factor * config.visuals.gizmo_size) as f64 * DOLLY_HANDLE_OFFSET;
+    config.translation - config.view_up() * offset
+}
+
+/// Picks the dolly handle, which is a dot on the screen.
+fn pick_dolly_handle(config: &PreparedGizmoConfig, ray: Ray) -> PickResult {
+    let position = dolly_handle_position(config);
+
+    let radius = config.visuals.stroke_width * DOLLY_HANDLE_RADIUS
+        + config.focus_distance / config.pixel_size.max(1e-10);
+    let picked = world_to_screen(config.viewport, config.view_projection, position)
+        .is_some_and(|screen_pos| screen_pos.distance(ray.screen_pos) <= radius);
+
+    PickResult {
+        subgizmo_point: config.translation,
+        visibility: 1.0,
+        picked,
+        t: (position - ray.origin).length(),
+    }
+}
+
+/// View direction pointing away from the camera
+fn view_axis_away_from_camera(config: &PreparedGizmoConfig, ray: Ray) -> DVec3 {
+    let axis = gizmo_normal(config, GizmoDirection::View);
+    if axis.dot(ray.direction) < 0.0 {
+        -axis
+    } else {
+        axis
+    }
+}
+
+/// Maps the vertical pointer motion since the subgizmo was picked
+/// to a point on the view axis. Moving the pointer up moves away from the camera.
+fn point_on_dolly_axis(subgizmo: &SubGizmoConfig<Translation>, ray: Ray) -> DVec3 {
+    let distance = (subgizmo.state.start_screen_pos.y - ray.screen_pos.y) as f64
+        * subgizmo.state.start_pixel_size;
+
+    subgizmo.state.start_point + subgizmo.state.dolly_axis * distance
+}
+
+/// Draws the dolly handle as a dot below the gizmo.
+#[cfg(feature = "draw")]
+fn draw_dolly_handle(subgizmo: &SubGizmoConfig<Translation>) -> GizmoDrawData {
+    let config = &subgizmo.config;
+    let shape_builder = ShapeBuidler::new(
+        config.view_projection,
+        config.viewport,
+        config.pixels_per_point,
+        config.visuals.circle_segments,
+    );
+
+    shape_builder
+        .dot(
+            dolly_handle_position(config),
+            config.visuals.stroke_width * DOLLY_HANDLE_RADIUS,
+            gizmo_color(config, subgizmo.focused, subgizmo.direction),
+        )
+        .into()
+}
+
+/// Axis or plane that the free translation is currently locked to, if any.
+/// Only the free translation subgizmo can be locked.
+fn axis_lock(subgizmo: &SubGizmoConfig<Translation>) -> Option<(GizmoDirection, TransformKind)> {
+    if subgizmo.direction == GizmoDirection::View && subgizmo.transform_kind == TransformKind::Plane
+    {
+        subgizmo.config.axis_lock
+    } else {
+        None
+    }
+}
+
+/// Projects a translation onto the axis or plane that the subgizmo is currently constrained to.
+fn constrain(subgizmo: &SubGizmoConfig<Translation>, translation: DVec3) -> DVec3 {
+    match subgizmo.state.axis_lock {
+        Some((direction, TransformKind::Axis)) => {
+            let axis = gizmo_normal(&subgizmo.config, direction);
+            axis * translation.dot(axis)
+        }
+        Some((direction, TransformKind::Plane)) => {
+            let normal = gizmo_normal(&subgizmo.config, direction);
+            translation - normal * translation.dot(normal)
+        }
+        None => {
+            let normal = subgizmo.state.plane_normal;
+            translation - normal * translation.dot(normal)
         }
     }
 }
 
-/// Finds the nearest point on line that points in translation subgizmo direction
-fn point_on_axis(subgizmo: &SubGizmoConfig<Translation>, ray: Ray) -> DVec3 {
+/// Finds the nearest point on line that points in the given direction
+fn point_on_axis(
+    subgizmo: &SubGizmoConfig<Translation>,
+    direction: GizmoDirection,
+    ray: Ray,
+) -> DVec3 {
     let origin = subgizmo.config.translation;
-    let direction = gizmo_normal(&subgizmo.config, subgizmo.direction);
+    let direction = gizmo_normal(&subgizmo.config, direction);
 
     let (_ray_t, subgizmo_t) = ray_to_ray(ray.origin, ray.direction, origin, direction);
 
@@ -149,19 +480,29 @@ fn point_on_plane(plane_normal: DVec3, plane_origin: DVec3, ray: Ray) -> Option<
     }
 }
 
-fn snap_translation_vector(subgizmo: &SubGizmoConfig<Translation>, new_delta: DVec3) -> DVec3 {
+fn snap_translation_vector(
+    subgizmo: &SubGizmoConfig<Translation>,
+    snap_fns: &SnapFunctions,
+    direction: GizmoDirection,
+    new_delta: DVec3,
+) -> DVec3 {
     let delta_length = new_delta.length();
     if delta_length > 1e-5 {
-        new_delta / delta_length
-            * round_to_interval(delta_length, subgizmo.config.snap_distance as f64)
+        let interval = subgizmo.config.snap_distance_along(local_axis(direction));
+        new_delta / delta_length * snap_distance(snap_fns, delta_length, interval)
     } else {
         new_delta
     }
 }
 
-fn snap_translation_plane(subgizmo: &SubGizmoConfig<Translation>, new_delta: DVec3) -> DVec3 {
-    let mut bitangent = plane_bitangent(subgizmo.direction);
-    let mut tangent = plane_tangent(subgizmo.direction);
+fn snap_translation_plane(
+    subgizmo: &SubGizmoConfig<Translation>,
+    snap_fns: &SnapFunctions,
+    direction: GizmoDirection,
+    new_delta: DVec3,
+) -> DVec3 {
+    let mut bitangent = plane_bitangent(direction);
+    let mut tangent = plane_tangent(direction);
     if subgizmo.config.local_space() {
         bitangent = subgizmo.config.rotation * bitangent;
         tangent = subgizmo.config.rotation * tangent;
@@ -170,14 +511,33 @@ fn snap_translation_plane(subgizmo: &SubGizmoConfig<Translation>, new_delta: DVe
     let ct = new_delta.cross(tangent);
     let lb = cb.length();
     let lt = ct.length();
-    let n = gizmo_normal(&subgizmo.config, subgizmo.direction);
+    let n = gizmo_normal(&subgizmo.config, direction);
+
+    let bitangent_interval = subgizmo
+        .config
+        .snap_distance_along(plane_bitangent(direction));
+    let tangent_interval = subgizmo
+        .config
+        .snap_distance_along(plane_tangent(direction));
 
     if lb > 1e-5 && lt > 1e-5 {
-        bitangent * round_to_interval(lt, subgizmo.config.snap_distance as f64) * (ct / lt).dot(n)
-            + tangent
-                * round_to_interval(lb, subgizmo.config.snap_distance as f64)
-                * (cb / lb).dot(n)
+        bitangent * snap_distance(snap_fns, lt, bitangent_interval) * (ct / lt).dot(n)
+            + tangent * snap_distance(snap_fns, lb, tangent_interval) * (cb / lb).dot(n)
     } else {
         new_delta
     }
 }
+
+fn snap_distance(snap_fns: &SnapFunctions, distance: f64, interval: f64) -> f64 {
+    snap_fns.snap(GizmoMode::Translate, distance, interval)
+}
+
+/// Local unit axis of the given direction, or zero for the view direction
+const fn local_axis(direction: GizmoDirection) -> DVec3 {
+    match direction {
+        GizmoDirection::X => DVec3::X,
+        GizmoDirection::Y => DVec3::Y,
+        GizmoDirection::Z => DVec3::Z,
+        GizmoDirection::View => DVec3::ZERO,
+    }
+}
@@ -0,0 +1,562 @@
+use crate::config::TransformPivotPoint;
+use crate::math::{world_to_screen, DQuat, DVec3, Pos2, Transform};
+use crate::prelude::*;
+
+/// Camera looking down the negative Z axis.
+const FRONT: DVec3 = DVec3::new(0.0, 0.0, 5.0);
+/// Camera looking at the origin at an angle to every axis.
+const OBLIQUE: DVec3 = DVec3::new(3.0, 2.0, 4.0);
+
+const FOV_Y: f64 = std::f64::consts::FRAC_PI_4;
+const VIEWPORT_HEIGHT: f32 = 600.0;
+
+/// Configuration for a perspective camera at `eye`, looking at the origin.
+fn config(eye: DVec3) -> GizmoConfig {
+    GizmoConfig::from_camera(
+        eye,
+        DVec3::ZERO,
+        DVec3::Y,
+        FOV_Y,
+        0.1,
+        100.0,
+        Rect::from_min_max(Pos2::ZERO, Pos2::new(800.0, VIEWPORT_HEIGHT)),
+    )
+}
+
+/// Gizmo updated once with a single target at the origin.
+fn gizmo(config: GizmoConfig) -> Gizmo {
+    let mut gizmo = Gizmo::new(config);
+    gizmo.update(GizmoInteraction::default(), &[Transform::default()]);
+    gizmo
+}
+
+/// World space length of the given number of pixels at the origin, seen from `eye`.
+fn pixels(eye: DVec3, count: f64) -> f64 {
+    count * 2.0 * eye.length() * (FOV_Y / 2.0).tan() / VIEWPORT_HEIGHT as f64
+}
+
+/// Right and up vectors of the camera at `eye`.
+fn camera_axes(eye: DVec3) -> (DVec3, DVec3) {
+    let forward = -eye.normalize();
+    let right = forward.cross(DVec3::Y).normalize();
+    (right, right.cross(forward))
+}
+
+fn screen_pos(gizmo: &Gizmo, point: DVec3) -> (f32, f32) {
+    let viewport = gizmo.config().viewport;
+    let pos = world_to_screen(viewport, gizmo.view_projection(), point).unwrap();
+    (pos.x, pos.y)
+}
+
+/// Subgizmo picked at the start of a drag, and the transformation the drag results in.
+#[derive(Debug)]
+struct Snapshot {
+    hit: GizmoHit,
+    result: GizmoResult,
+}
+
+impl Snapshot {
+    /// Drags whichever subgizmo is under `from` to `to`, both given in world space.
+    fn drag(gizmo: &Gizmo, from: DVec3, to: DVec3) -> Self {
+        let (from, to) = (screen_pos(gizmo, from), screen_pos(gizmo, to));
+
+        let hit = gizmo.pick_preview(from).expect("nothing to pick");
+        let result = gizmo
+            .simulate_drag(hit.mode, hit.direction, from, to)
+            .expect("the drag did not transform");
+
+        Self { hit, result }
+    }
+
+    fn assert_picked(&self, mode: GizmoMode, direction: GizmoDirection, kind: TransformKind) {
+        let hit = (self.hit.mode, self.hit.direction, self.hit.transform_kind);
+        assert_eq!(hit, (mode, direction, kind), "{self:?}");
+
+        if let Some(result_direction) = self.result.direction() {
+            assert_eq!(result_direction, direction, "{self:?}");
+            assert_eq!(self.result.transform_kind(), Some(kind), "{self:?}");
+        }
+    }
+}
+
+#[test]
+fn translation_arrow_snapshot() {
+    for eye in [FRONT, OBLIQUE] {
+        let gizmo = gizmo(GizmoConfig {
+            modes: enum_set!(GizmoMode::Translate),
+            ..config(eye)
+        });
+
+        let from = DVec3::X * pixels(eye, 50.0);
+        let snapshot = Snapshot::drag(&gizmo, from, from * 2.0);
+        snapshot.assert_picked(GizmoMode::Translate, GizmoDirection::X, TransformKind::Axis);
+
+        let total = snapshot.result.total_translation().unwrap();
+        assert!((total - from).length() < 1e-3, "{snapshot:?}");
+    }
+}
+
+#[test]
+fn scale_arrow_snapshot() {
+    for eye in [FRONT, OBLIQUE] {
+        let gizmo = gizmo(GizmoConfig {
+            modes: enum_set!(GizmoMode::Scale),
+            ..config(eye)
+        });
+
+        let from = DVec3::X * pixels(eye, 50.0);
+        let snapshot = Snapshot::drag(&gizmo, from, from * 2.0);
+        snapshot.assert_picked(GizmoMode::Scale, GizmoDirection::X, TransformKind::Axis);
+
+        let total = snapshot.result.total_scale().unwrap();
+        assert!(total.x > 1.5, "{snapshot:?}");
+        assert_eq!((total.y, total.z), (1.0, 1.0), "{snapshot:?}");
+        if eye == FRONT {
+            assert!((total.x - 2.0).abs() < 1e-3, "{snapshot:?}");
+        }
+    }
+}
+
+#[test]
+fn rotation_ring_snapshot() {
+    for eye in [FRONT, OBLIQUE] {
+        let gizmo = gizmo(GizmoConfig {
+            modes: enum_set!(GizmoMode::Rotate),
+            ..config(eye)
+        });
+
+        // The side of the Z ring facing the camera
+        let toward_eye = DVec3::new(eye.x, eye.y, 0.0)
+            .try_normalize()
+            .unwrap_or_else(|| DVec3::new(1.0, 1.0, 0.0).normalize());
+        let from = toward_eye * pixels(eye, 75.0);
+        let to = DQuat::from_rotation_z(30f64.to_radians()) * from;
+
+        let snapshot = Snapshot::drag(&gizmo, from, to);
+        snapshot.assert_picked(GizmoMode::Rotate, GizmoDirection::Z, TransformKind::Axis);
+
+        // Angles are measured on the screen, which matches the ring only when facing it
+        let rotated = snapshot.result.total_rotation().unwrap() * from;
+        assert!(rotated.distance(to) < from.distance(to), "{snapshot:?}");
+        if eye == FRONT {
+            assert!(rotated.distance(to) < 1e-3, "{snapshot:?}");
+        }
+    }
+}
+
+#[test]
+fn view_ring_snapshot() {
+    for eye in [FRONT, OBLIQUE] {
+        let gizmo = gizmo(GizmoConfig {
+            modes: enum_set!(GizmoMode::Rotate),
+            ..config(eye)
+        });
+
+        // The view ring surrounds the axis rings
+        let (right, up) = camera_axes(eye);
+        let radius = pixels(eye, 84.0);
+        let from = (right + up).normalize() * radius;
+        let to = (up - right).normalize() * radius;
+
+        let snapshot = Snapshot::drag(&gizmo, from, to);
+        snapshot.assert_picked(GizmoMode::Rotate, GizmoDirection::View, TransformKind::Axis);
+
+        let GizmoResult::Rotation { is_view_axis, .. } = snapshot.result else {
+            panic!("expected a rotation: {snapshot:?}");
+        };
+        assert!(is_view_axis);
+
+        let rotated = snapshot.result.total_rotation().unwrap() * from;
+        assert!(rotated.distance(to) < 1e-3, "{snapshot:?}");
+    }
+}
+
+#[test]
+fn arcball_snapshot() {
+    for eye in [FRONT, OBLIQUE] {
+        let gizmo = gizmo(GizmoConfig {
+            modes: enum_set!(GizmoMode::Rotate),
+            ..config(eye)
+        });
+
+        let (right, _) = camera_axes(eye);
+        let snapshot = Snapshot::drag(&gizmo, DVec3::ZERO, right * pixels(eye, 20.0));
+        snapshot.assert_picked(
+            GizmoMode::Rotate,
+            GizmoDirection::View,
+            TransformKind::Plane,
+        );
+
+        let GizmoResult::Arcball { total, .. } = snapshot.result else {
+            panic!("expected an arcball rotation: {snapshot:?}");
+        };
+        assert!(DQuat::from(total).angle_between(DQuat::IDENTITY) > 0.0);
+    }
+}
+
+#[test]
+fn screen_bounds_cover_the_gizmo() {
+    let mut gizmo = gizmo(config(FRONT));
+
+    let bounds = gizmo.screen_bounds().unwrap();
+    assert!(bounds.contains(Pos2::new(400.0, 300.0)));
+    assert!(bounds.width() >= 2.0 * gizmo.config().visuals.gizmo_size);
+
+    let behind_camera = Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        DQuat::IDENTITY,
+        DVec3::new(0.0, 0.0, 10.0),
+    );
+    gizmo.update(GizmoInteraction::default(), &[behind_camera]);
+    assert!(gizmo.screen_bounds().is_none());
+}
+
+#[test]
+fn simulate_drag_leaves_the_gizmo_untouched() {
+    let gizmo = gizmo(GizmoConfig {
+        modes: enum_set!(GizmoMode::Translate),
+        ..config(FRONT)
+    });
+
+    let from = screen_pos(&gizmo, DVec3::X * pixels(FRONT, 50.0));
+    let to = screen_pos(&gizmo, DVec3::X * pixels(FRONT, 150.0));
+
+    let result = gizmo.simulate_drag(GizmoMode::Translate, GizmoDirection::X, from, to);
+    let total = result.unwrap().total_translation().unwrap();
+    assert!(total.x > 0.0);
+    assert!(total.y.abs() < 1e-6 && total.z.abs() < 1e-6);
+    assert!(gizmo.active().is_none());
+
+    // Only the subgizmo of the given direction is dragged
+    let result = gizmo.simulate_drag(GizmoMode::Translate, GizmoDirection::Y, from, to);
+    assert!(result.is_none());
+}
+
+#[test]
+fn cancelling_reverts_the_drag() {
+    let mut gizmo = gizmo(GizmoConfig {
+        modes: enum_set!(GizmoMode::Translate),
+        ..config(FRONT)
+    });
+    let start = [Transform::default()];
+
+    let drag = |cursor_pos, drag_started, cancel| GizmoInteraction {
+        cursor_pos,
+        drag_started,
+        dragging: true,
+        cancel,
+        ..Default::default()
+    };
+    let from = screen_pos(&gizmo, DVec3::X * pixels(FRONT, 50.0));
+    let to = screen_pos(&gizmo, DVec3::X * pixels(FRONT, 100.0));
+
+    gizmo.update(drag(from, true, false), &start);
+    let (_, moved) = gizmo.update(drag(to, false, false), &start).unwrap();
+    assert!(moved[0].translation.x > 0.0);
+
+    let (result, reverted) = gizmo.update(drag(to, false, true), &moved).unwrap();
+    assert!(gizmo.drag_cancelled());
+    assert_eq!(reverted[0], start[0]);
+    assert_eq!(result.total_translation(), Some(DVec3::ZERO));
+
+    // The drag stays cancelled while the pointer is held
+    assert!(gizmo.update(drag(from, false, false), &reverted).is_none());
+}
+
+#[test]
+fn pinching_scales_uniformly() {
+    let mut gizmo = gizmo(GizmoConfig {
+        modes: enum_set!(GizmoMode::Scale),
+        ..config(FRONT)
+    });
+    let targets = [Transform::default()];
+
+    let cursor_pos = screen_pos(&gizmo, DVec3::X * pixels(FRONT, 10.0));
+    let pinch = |pinch_zoom| GizmoInteraction {
+        cursor_pos,
+        pinch_zoom: Some(pinch_zoom),
+        ..Default::default()
+    };
+    gizmo.update(pinch(1.5), &targets);
+    let (result, scaled) = gizmo.update(pinch(2.0), &targets).unwrap();
+
+    assert_eq!(result.total_scale(), Some(DVec3::splat(3.0)));
+    assert_eq!(result.delta_scale(), Some(DVec3::splat(2.0)));
+    assert_eq!(DVec3::from(scaled[0].scale), DVec3::splat(3.0));
+}
+
+#[test]
+fn scaling_moves_targets_away_from_the_pivot() {
+    let scale_targets = |pivot_point| {
+        let mut gizmo = gizmo(GizmoConfig {
+            modes: enum_set!(GizmoMode::Scale),
+            pivot_point,
+            ..config(FRONT)
+        });
+        let targets = [
+            Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, -DVec3::X),
+            Transform::from_scale_rotation_translation(DVec3::ONE, DQuat::IDENTITY, DVec3::X),
+        ];
+        let center = match pivot_point {
+            TransformPivotPoint::ActiveElement => DVec3::X,
+            _ => DVec3::ZERO,
+        };
+
+        let interaction = GizmoInteraction {
+            cursor_pos: screen_pos(&gizmo, center),
+            pinch_zoom: Some(2.0),
+            ..Default::default()
+        };
+        let (_, scaled) = gizmo.update(interaction, &targets).unwrap();
+
+        scaled
+            .iter()
+            .map(|target| {
+                assert_eq!(DVec3::from(target.scale), DVec3::splat(2.0));
+                DVec3::from(target.translation).x
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(scale_targets(TransformPivotPoint::MedianPoint), [-2.0, 2.0]);
+    assert_eq!(
+        scale_targets(TransformPivotPoint::IndividualOrigins),
+        [-1.0, 1.0]
+    );
+    assert_eq!(
+        scale_targets(TransformPivotPoint::ActiveElement),
+        [-3.0, 1.0]
+    );
+}
+
+#[test]
+fn single_rotation_axis_leaves_out_free_rotation() {
+    let can_rotate_freely = |rotation_directions| {
+        let gizmo = gizmo(GizmoConfig {
+            modes: enum_set!(GizmoMode::Rotate),
+            rotation_directions,
+            ..config(FRONT)
+        });
+
+        // The arcball fills the rings
+        gizmo
+            .pick_preview(screen_pos(&gizmo, DVec3::ZERO))
+            .is_some_and(|hit| hit.direction == GizmoDirection::View)
+    };
+
+    assert!(can_rotate_freely(EnumSet::all()));
+    assert!(can_rotate_freely(
+        GizmoDirection::Y | GizmoDirection::Z | GizmoDirection::View
+    ));
+    assert!(!can_rotate_freely(GizmoDirection::Y | GizmoDirection::View));
+}
+
+#[test]
+fn planar_gizmo_stays_on_the_xy_plane() {
+    let mut gizmo = gizmo(GizmoConfig {
+        modes: GizmoMode::Rotate | GizmoMode::Translate | GizmoMode::Scale,
+        planar_2d: true,
+        ..config(FRONT)
+    });
+    let targets = [Transform::default()];
+
+    // Over the X axis arrow, which is pushed outwards by the scale handle
+    let from = screen_pos(&gizmo, DVec3::X * pixels(FRONT, 95.0));
+    let to = screen_pos(&gizmo, DVec3::X * pixels(FRONT, 150.0));
+    let drag = |mode, direction| gizmo.simulate_drag(mode, direction, from, to);
+
+    let total = drag(GizmoMode::Translate, GizmoDirection::X)
+        .and_then(|result| result.total_translation())
+        .unwrap();
+    assert!(total.x > 0.0 && total.z.abs() < 1e-6);
+
+    assert!(drag(GizmoMode::Translate, GizmoDirection::View).is_none());
+    assert!(drag(GizmoMode::Rotate, GizmoDirection::X).is_none());
+    assert!(drag(GizmoMode::Scale, GizmoDirection::View).is_none());
+
+    // Locking to the Z axis or to the YZ plane keeps the targets on the XY plane
+    let off_axis = screen_pos(&gizmo, DVec3::new(150.0, 50.0, 0.0) * pixels(FRONT, 1.0));
+    let interaction = |drag_started, axis_lock_key| GizmoInteraction {
+        cursor_pos: if drag_started { from } else { off_axis },
+        drag_started,
+        dragging: true,
+        axis_lock_key,
+        ..Default::default()
+    };
+    gizmo.update(interaction(true, None), &targets);
+    for key in [GizmoDirection::Z, GizmoDirection::X, GizmoDirection::X] {
+        let (_, moved) = gizmo
+            .update(interaction(false, Some(key)), &targets)
+            .unwrap();
+        assert!(moved[0].translation.z.abs() < 1e-9);
+    }
+    gizmo.update(GizmoInteraction::default(), &targets);
+
+    // Pinching leaves the Z axis unscaled
+    let pinch = GizmoInteraction {
+        cursor_pos: screen_pos(&gizmo, DVec3::X * pixels(FRONT, 10.0)),
+        pinch_zoom: Some(2.0),
+        ..Default::default()
+    };
+    let (result, _) = gizmo.update(pinch, &targets).unwrap();
+    assert_eq!(result.total_scale(), Some(DVec3::new(2.0, 2.0, 1.0)));
+}
+
+#[test]
+fn cursor_origin_does_not_change_the_rotation() {
+    // Drags the Z ring a quarter turn, from the right of the gizmo to above it
+    let drag = |viewport_y_down: bool| {
+        let mut gizmo = gizmo(GizmoConfig {
+            modes: enum_set!(GizmoMode::Rotate),
+            viewport_y_down,
+            ..config(FRONT)
+        });
+        let targets = [Transform::default()];
+        let radius = pixels(FRONT, 75.0);
+
+        let mut total = 0.0;
+        for (i, point) in [DVec3::X * radius, DVec3::Y * radius]
+            .into_iter()
+            .enumerate()
+        {
+            let (x, y) = screen_pos(&gizmo, point);
+            let interaction = GizmoInteraction {
+                cursor_pos: if viewport_y_down {
+                    (x, y)
+                } else {
+                    (x, VIEWPORT_HEIGHT - y)
+                },
+                drag_started: i == 0,
+                dragging: true,
+                ..Default::default()
+            };
+            if let Some((GizmoResult::Rotation { total: t, .. }, _)) =
+                gizmo.update(interaction, &targets)
+            {
+                total = t;
+            }
+        }
+        total
+    };
+
+    let top_left = drag(true);
+    let bottom_left = drag(false);
+
+    assert!(top_left.abs() > 1.0);
+    assert!((top_left - bottom_left).abs() < 1e-6);
+}
+
+#[test]
+fn transparent_gizmo_is_not_picked() {
+    let is_picked = |opacity| {
+        let gizmo = gizmo(GizmoConfig {
+            opacity,
+            ..config(FRONT)
+        });
+        gizmo
+            .pick_preview(screen_pos(&gizmo, DVec3::ZERO))
+            .is_some()
+    };
+
+    assert!(is_picked(1.0));
+    assert!(!is_picked(0.0));
+}
+
+#[test]
+fn non_interactive_gizmo_ignores_the_pointer() {
+    let mut gizmo = gizmo(config(FRONT));
+    let targets = [Transform::default()];
+
+    let center = screen_pos(&gizmo, DVec3::ZERO);
+    let interaction = GizmoInteraction {
+        cursor_pos: center,
+        drag_started: true,
+        dragging: true,
+        ..Default::default()
+    };
+    gizmo.update(interaction, &targets);
+    assert!(gizmo.active().is_some());
+
+    // Turning interaction off drops the ongoing drag
+    gizmo.update_config(GizmoConfig {
+        interactive: false,
+        ..config(FRONT)
+    });
+    assert!(gizmo.active().is_none());
+
+    assert!(gizmo.update(interaction, &targets).is_none());
+    assert!(gizmo.pick_preview(center).is_none());
+    assert!(gizmo.active().is_none());
+}
+
+#[cfg(feature = "draw")]
+#[test]
+fn tiny_snap_angle_is_cheap_to_draw() {
+    let mut gizmo = gizmo(GizmoConfig {
+        snapping: true,
+        snap_angle: 1e-9,
+        ..config(FRONT)
+    });
+    let targets = [Transform::default()];
+
+    // Grab the Z axis ring, which faces the camera, and rotate it
+    let radius = pixels(FRONT, 75.0);
+    let drag = |cursor_pos, drag_started| GizmoInteraction {
+        cursor_pos,
+        drag_started,
+        dragging: true,
+        ..Default::default()
+    };
+    let from = screen_pos(&gizmo, DVec3::new(1.0, 1.0, 0.0).normalize() * radius);
+    let to = screen_pos(&gizmo, DVec3::X * radius);
+    gizmo.update(drag(from, true), &targets);
+    gizmo.update(drag(to, false), &targets);
+    assert_eq!(gizmo.active(), Some((GizmoMode::Rotate, GizmoDirection::Z)));
+
+    let draw_data = gizmo.draw();
+    assert!(!draw_data.vertices.is_empty());
+    assert!(draw_data.vertices.len() < 100_000);
+}
+
+#[test]
+fn distant_world_sized_gizmo_keeps_pixel_pick_tolerance() {
+    let mut gizmo = Gizmo::new(GizmoConfig {
+        modes: enum_set!(GizmoMode::Translate),
+        size_mode: SizeMode::WorldUnits(2.0),
+        ..config(FRONT)
+    });
+
+    // Far enough from the camera that the gizmo is only about 30 pixels in size
+    let distant = Transform::from_scale_rotation_translation(
+        DVec3::ONE,
+        DQuat::IDENTITY,
+        DVec3::new(0.0, 0.0, -45.0),
+    );
+    gizmo.update(GizmoInteraction::default(), &[distant]);
+
+    // The X axis arrow can still be grabbed a few pixels away from it
+    let (x, y) = screen_pos(&gizmo, DVec3::from(distant.translation) + DVec3::X);
+    let hit = gizmo.hit_test((x, y + 5.0), GizmoMode::Translate, GizmoDirection::X);
+    assert!(hit.is_some());
+}
+
+#[test]
+fn translation_and_scale_handles_do_not_overlap() {
+    // Isometric view with all modes enabled
+    let gizmo = gizmo(GizmoConfig {
+        modes: EnumSet::all(),
+        ..config(DVec3::splat(4.0))
+    });
+
+    let (center_x, center_y) = screen_pos(&gizmo, DVec3::ZERO);
+    for x in (-120..120).step_by(3) {
+        for y in (-120..120).step_by(3) {
+            let pos = (center_x + x as f32, center_y + y as f32);
+            for direction in [GizmoDirection::X, GizmoDirection::Y, GizmoDirection::Z] {
+                let translate = gizmo.hit_test(pos, GizmoMode::Translate, direction);
+                let scale = gizmo.hit_test(pos, GizmoMode::Scale, direction);
+                assert!(translate.is_none() || scale.is_none(), "{pos:?}");
+            }
+        }
+    }
+}
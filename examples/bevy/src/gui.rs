@@ -23,12 +23,10 @@ fn update_ui(
 
     gizmo_targets: Query<&GizmoTarget>,
 ) {
-    // Snapping is enabled when CTRL is pressed.
-    let snapping = keyboard_input.pressed(KeyCode::ControlLeft);
+    // Snapping is toggled by the gizmo plugin while CTRL is pressed.
     // Accurate snapping is enabled when both CTRL and SHIFT are pressed
-    let accurate_snapping = snapping && keyboard_input.pressed(KeyCode::ShiftLeft);
-
-    gizmo_options.snapping = snapping;
+    let accurate_snapping =
+        keyboard_input.pressed(KeyCode::ControlLeft) && keyboard_input.pressed(KeyCode::ShiftLeft);
 
     gizmo_options.snap_angle = DEFAULT_SNAP_ANGLE;
     gizmo_options.snap_distance = DEFAULT_SNAP_DISTANCE;
@@ -58,12 +56,7 @@ fn update_ui(
 fn draw_gizmo_result(ui: &mut egui::Ui, gizmo_result: Option<GizmoResult>) {
     if let Some(result) = gizmo_result {
         let text = match result {
-            GizmoResult::Rotation {
-                axis,
-                delta: _,
-                total,
-                is_view_axis: _,
-            } => {
+            GizmoResult::Rotation { axis, total, .. } => {
                 format!(
                     "Rotation axis: ({:.2}, {:.2}, {:.2}), Angle: {:.2} deg",
                     axis.x,
@@ -72,13 +65,13 @@ fn draw_gizmo_result(ui: &mut egui::Ui, gizmo_result: Option<GizmoResult>) {
                     total.to_degrees()
                 )
             }
-            GizmoResult::Translation { delta: _, total } => {
+            GizmoResult::Translation { total, .. } => {
                 format!(
                     "Translation: ({:.2}, {:.2}, {:.2})",
                     total.x, total.y, total.z,
                 )
             }
-            GizmoResult::Scale { total } => {
+            GizmoResult::Scale { total, .. } => {
                 format!("Scale: ({:.2}, {:.2}, {:.2})", total.x, total.y, total.z,)
             }
             GizmoResult::Arcball { delta: _, total } => {
@@ -141,6 +134,7 @@ fn draw_options(ui: &mut egui::Ui, gizmo_options: &mut GizmoOptions) {
                     for pivot_point in [
                         TransformPivotPoint::MedianPoint,
                         TransformPivotPoint::IndividualOrigins,
+                        TransformPivotPoint::ActiveElement,
                     ] {
                         ui.selectable_value(
                             &mut gizmo_options.pivot_point,
@@ -41,16 +41,13 @@ impl ExampleApp {
         // Fixed camera position
         let view_matrix = DMat4::look_at_lh(DVec3::splat(5.0), DVec3::ZERO, DVec3::Y);
 
-        // Ctrl toggles snapping
-        let snapping = ui.input(|input| input.modifiers.ctrl);
-
         self.gizmo.update_config(GizmoConfig {
             view_matrix: view_matrix.into(),
             projection_matrix: projection_matrix.into(),
             viewport,
             modes: self.gizmo_modes,
             orientation: self.gizmo_orientation,
-            snapping,
+            // Snapping is toggled with Ctrl by `interact`
             ..Default::default()
         });
 
@@ -69,12 +66,7 @@ impl ExampleApp {
             self.translation = transform.translation.into();
 
             let text = match result {
-                GizmoResult::Rotation {
-                    axis,
-                    delta: _,
-                    total,
-                    is_view_axis: _,
-                } => {
+                GizmoResult::Rotation { axis, total, .. } => {
                     format!(
                         "Rotation axis: ({:.2}, {:.2}, {:.2}), Angle: {:.2} deg",
                         axis.x,
@@ -83,13 +75,13 @@ impl ExampleApp {
                         total.to_degrees()
                     )
                 }
-                GizmoResult::Translation { delta: _, total } => {
+                GizmoResult::Translation { total, .. } => {
                     format!(
                         "Translation: ({:.2}, {:.2}, {:.2})",
                         total.x, total.y, total.z,
                     )
                 }
-                GizmoResult::Scale { total } => {
+                GizmoResult::Scale { total, .. } => {
                     format!("Scale: ({:.2}, {:.2}, {:.2})", total.x, total.y, total.z,)
                 }
                 GizmoResult::Arcball { delta: _, total } => {